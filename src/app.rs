@@ -1,9 +1,8 @@
 use std::{
     collections::{HashMap, HashSet},
     fs,
-    io::Read,
     path::PathBuf,
-    sync::{Arc, Mutex, mpsc},
+    sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex, mpsc},
     thread,
     time::{Duration, Instant},
 };
@@ -18,15 +17,144 @@ struct Notification {
     position: f32,
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum NotificationType {
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum NotificationType {
     Info,
     Success,
-    #[allow(dead_code)]
     Warning,
     Error,
 }
 
+impl NotificationType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationType::Info => "info",
+            NotificationType::Success => "success",
+            NotificationType::Warning => "warning",
+            NotificationType::Error => "error",
+        }
+    }
+}
+
+/// Number of entries kept in the notification history.
+pub const HISTORY_LIMIT: usize = 500;
+
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub severity: NotificationType,
+    /// `false` for notifications, `true` for changes of the status line.
+    pub from_status: bool,
+    pub message: String,
+}
+
+impl HistoryEntry {
+    pub fn line(&self) -> String {
+        format!(
+            "[{}] {}{}: {}",
+            self.time.format("%Y-%m-%d %H:%M:%S"),
+            self.severity.label(),
+            if self.from_status { " (status)" } else { "" },
+            self.message
+        )
+    }
+}
+
+/// Every notification and status change, oldest first, bounded by `HISTORY_LIMIT`.
+#[derive(Default)]
+pub struct NotificationHistory {
+    pub entries: std::collections::VecDeque<HistoryEntry>,
+    pub open: bool,
+    pub filter: String,
+    /// Lowest severity shown in the panel.
+    pub min_severity: Option<NotificationType>,
+    /// Errors recorded since the panel was last opened.
+    pub unread_errors: usize,
+    /// Last status line seen, to record only changes.
+    last_status: String,
+}
+
+impl NotificationHistory {
+    fn push(&mut self, severity: NotificationType, from_status: bool, message: String) {
+        let level = match severity {
+            NotificationType::Error => log::Level::Error,
+            NotificationType::Warning => log::Level::Warn,
+            _ => log::Level::Info,
+        };
+        log::log!(level, "{}", message);
+        if self.entries.len() >= HISTORY_LIMIT {
+            self.entries.pop_front();
+        }
+        if severity == NotificationType::Error && !self.open {
+            self.unread_errors += 1;
+        }
+        self.entries.push_back(HistoryEntry { time: chrono::Local::now(), severity, from_status, message });
+    }
+
+    /// Entries matching the severity and text filters, newest first.
+    pub fn filtered(&self) -> Vec<&HistoryEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| self.min_severity.is_none_or(|min| entry.severity >= min))
+            .filter(|entry| contains_ignore_case(&entry.message, &self.filter))
+            .collect()
+    }
+}
+
+/// Keyboard cursor in a list of cards or rows.
+#[derive(Default)]
+pub struct ListCursor {
+    pub index: Option<usize>,
+    /// The cursor was moved and still has to be scrolled into view.
+    pub reveal: bool,
+    /// Rows rendered in the last frame.
+    pub shown: std::ops::Range<usize>,
+}
+
+impl ListCursor {
+    /// Moves the cursor by `delta` rows in a list of `len` rows. Without a cursor, starts at
+    /// the first row on screen.
+    pub fn step(&mut self, delta: isize, len: usize) {
+        if len == 0 {
+            self.index = None;
+            return;
+        }
+        self.index = Some(match self.index {
+            Some(index) => index.saturating_add_signed(delta).min(len - 1),
+            None => self.shown.start.min(len - 1),
+        });
+        self.reveal = true;
+    }
+
+    /// Cursor row, if it is inside a list of `len` rows.
+    pub fn get(&self, len: usize) -> Option<usize> {
+        self.index.filter(|index| *index < len)
+    }
+
+    /// Scroll offset that brings the cursor on screen when it is outside the rows rendered
+    /// in the last frame. Once on screen, the row itself scrolls into view.
+    pub fn scroll_offset(&self, row_height: f32) -> Option<f32> {
+        let index = self.index?;
+        (self.reveal && !self.shown.contains(&index)).then_some(index as f32 * row_height)
+    }
+}
+
+/// State of the log viewer window.
+pub struct LogViewer {
+    pub open: bool,
+    /// Most verbose level shown.
+    pub max_level: log::LevelFilter,
+    pub target: String,
+    pub filter: String,
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self { open: false, max_level: log::LevelFilter::Trace, target: String::new(), filter: String::new() }
+    }
+}
+
 #[derive(Deserialize)]
 struct GitHubRelease {
     tag_name: String,
@@ -77,29 +205,82 @@ struct UpdateInfo {
 
 use eframe::egui::{self, CentralPanel, Context};
 use eframe::{App, CreationContext};
-use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
 
+use crate::tools::address_book::{AddressBook, Contact};
+use crate::tools::auto_download::{plan_downloads, AutoDownloadRule};
+use crate::tools::script::ScriptHost;
+use crate::tools::tracking::{append_to_log, find_sightings, is_tracked, send_webhook, SeenSightings, TrackedPlayer};
+use crate::tools::batch::{
+    download_listed, parse_replay_ids, run_batch, BatchEvent, BatchOptions, BatchSummary, DEFAULT_JOBS,
+};
+use crate::tools::http_client::{self, NetworkSettings, INTERACTIVE_TIMEOUT};
+use crate::tools::library::{record_download, scan_downloaded_ids, settings_dir, write_downloaded_replay, write_replay_file, LibraryIndex};
+use crate::tools::players::{aggregate_players, PlayerSort, PlayerStats};
+use crate::tools::live::{fetch_live_replays, is_recorded, LIVE_REFRESH_INTERVAL, LIVE_WAIT_LIMIT};
+use crate::tools::listing::{fetch_page, search_all_pages, total_pages, PAGE_SIZE};
+use crate::tools::listing::contains_ignore_case;
+use crate::tools::logging::DEFAULT_LOG_FILTER;
+pub use crate::tools::listing::{PlatformFilter, ReplayFilters, ReplaySort, SortKey};
+use crate::tools::naming::{local_replay_filename, replay_filename, NamingOptions};
+use crate::tools::watchlist::{update_expiry, WatchedReplay, DEFAULT_EXPIRY_WINDOW_HOURS};
 use crate::tools::replay_processor::{
-    download_replay, process_replay, ApiResponse, Config, DownloadProgress,
-    MetaData, Progress, ReplayItem, API_BASE_URL,
+    download_replay, fetch_metadata, parse_mod_ids, process_replay, replay_chunks_dir, ApiReplay, Config,
+    DownloadProgress, MetaData, Progress, ReplayItem,
 };
 
 use crate::pages;
+use crate::pages::{scan_downloaded_replays, DownloadedReplayInfo, UNKNOWN_REPLAY_ID};
 
 type DownloadedReplaysSender = std::sync::mpsc::Sender<String>;
 type DownloadedReplaysReceiver = std::sync::mpsc::Receiver<String>;
 type UpdateInfoReceiver = std::sync::mpsc::Receiver<UpdateInfo>;
 
+enum AutoDownloadMessage {
+    Downloaded(String),
+    /// Already in the target folder.
+    Skipped(String),
+    Failed { id: String, error: String },
+    Finished { downloaded: usize, skipped: usize, failed: usize },
+}
+
+/// Result of a background listing fetch, tagged with the request it answers so responses to
+/// superseded refreshes can be dropped.
+struct ListingResponse {
+    request: u64,
+    result: Result<(Vec<ReplayItem>, usize), String>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub download_dir: PathBuf,
     pub auto_refresh_enabled: bool,
     pub auto_refresh_interval_mins: u64,
     pub auto_download_enabled: bool,
-    pub auto_download_trigger_user_id: String,
+    pub auto_download_rules: Vec<AutoDownloadRule>,
+    /// Optional rhai script deciding about replays no rule selects. Empty disables it.
+    pub auto_download_script: PathBuf,
+    /// Single trigger user ID of older versions, turned into a rule when settings are loaded.
+    #[serde(rename = "auto_download_trigger_user_id", skip_serializing)]
+    legacy_trigger_user_id: String,
     pub modio_api_url: String,
     pub modio_api_token: String,
+    pub network: NetworkSettings,
+    pub naming: NamingOptions,
+    pub replay_sort: ReplaySort,
+    pub watchlist: Vec<WatchedReplay>,
+    /// Download watched replays automatically once they are about to expire.
+    pub watchlist_auto_download: bool,
+    pub watchlist_expiry_window_hours: u64,
+    pub tracked_players: Vec<TrackedPlayer>,
+    /// File tracked-player alerts are appended to. Empty disables the log.
+    pub tracking_log_file: PathBuf,
+    /// URL tracked-player alerts are posted to as JSON. Empty disables the webhook.
+    pub tracking_webhook_url: String,
+    pub address_book: AddressBook,
+    /// Log filter like `info` or `warn,tools::batch=debug`, see `LogFilter`.
+    pub log_level: String,
 }
 
 impl Default for Settings {
@@ -109,32 +290,39 @@ impl Default for Settings {
             auto_refresh_enabled: true,
             auto_refresh_interval_mins: 5,
             auto_download_enabled: false,
-            auto_download_trigger_user_id: String::new(),
+            auto_download_rules: Vec::new(),
+            auto_download_script: PathBuf::new(),
+            legacy_trigger_user_id: String::new(),
             modio_api_url: "https://api.mod.io/v1".to_string(),
             modio_api_token: String::new(),
+            network: NetworkSettings::default(),
+            naming: NamingOptions::default(),
+            replay_sort: ReplaySort::default(),
+            watchlist: Vec::new(),
+            watchlist_auto_download: true,
+            watchlist_expiry_window_hours: DEFAULT_EXPIRY_WINDOW_HOURS,
+            tracked_players: Vec::new(),
+            tracking_log_file: PathBuf::new(),
+            tracking_webhook_url: String::new(),
+            address_book: AddressBook::default(),
+            log_level: DEFAULT_LOG_FILTER.to_string(),
         }
     }
 }
 
-#[derive(Clone, Default)]
-pub struct ReplayFilters {
-    pub game_mode: String,
-    pub map_name: String,
-    pub workshop_mods: String,
-    pub platform: PlatformFilter,
-    pub user_id: String,
-}
-
-#[derive(Clone, Copy, PartialEq, Debug)]
-pub enum PlatformFilter {
-    All,
-    Quest,
-    PC,
-}
+impl Settings {
+    fn migrate(&mut self) {
+        let legacy_trigger = std::mem::take(&mut self.legacy_trigger_user_id);
+        if !legacy_trigger.is_empty() && self.auto_download_rules.is_empty() {
+            self.auto_download_rules.push(AutoDownloadRule::for_user(&legacy_trigger));
+        }
+    }
 
-impl Default for PlatformFilter {
-    fn default() -> Self {
-        Self::All
+    /// Whether any auto-download rule or the download script can select a replay.
+    pub fn has_active_rules(&self) -> bool {
+        self.auto_download_enabled
+            && (!self.auto_download_script.as_os_str().is_empty()
+                || self.auto_download_rules.iter().any(|rule| rule.enabled && !rule.is_empty()))
     }
 }
 
@@ -144,6 +332,136 @@ pub struct ReplayListState {
     pub current_page: usize,
     pub total_pages: usize,
     pub filters: ReplayFilters,
+    /// A fetch is in flight. `replays` still holds the previous page until it completes.
+    pub is_loading: bool,
+    /// IDs of the replays ticked for bulk actions. Kept across pages and searches.
+    pub selected: HashSet<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchEntryStatus {
+    Queued,
+    Downloading { current: usize, total: usize },
+    Done(PathBuf),
+    Skipped(String),
+    Failed(String),
+}
+
+pub struct BatchDownloadState {
+    pub open: bool,
+    pub ids_text: String,
+    pub jobs: usize,
+    pub running: bool,
+    pub entries: Arc<Mutex<Vec<(String, BatchEntryStatus)>>>,
+    pub result: Arc<Mutex<Option<Result<BatchSummary, String>>>>,
+}
+
+impl Default for BatchDownloadState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            ids_text: String::new(),
+            jobs: DEFAULT_JOBS,
+            running: false,
+            entries: Arc::new(Mutex::new(Vec::new())),
+            result: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+pub enum SearchEvent {
+    Matches { replays: Vec<ReplayItem>, scanned: usize, total: usize },
+    Finished,
+    Failed(String),
+}
+
+/// "Search all pages" mode of the replay list: walks every page of the catalogue on a
+/// background thread and collects the replays matching the filters.
+#[derive(Default)]
+pub struct CatalogueSearch {
+    /// Results are shown instead of the current page while this is set.
+    pub active: bool,
+    pub running: bool,
+    pub results: Vec<ReplayItem>,
+    pub scanned: usize,
+    pub total: usize,
+    cancel: Arc<AtomicBool>,
+    rx: Option<mpsc::Receiver<SearchEvent>>,
+}
+
+impl CatalogueSearch {
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.scanned.min(self.total) as f32) / (self.total as f32)
+        }
+    }
+}
+
+struct LiveResponse {
+    result: Result<Vec<ReplayItem>, String>,
+    /// Awaited matches whose recording has finished.
+    recorded: Vec<String>,
+}
+
+/// State of the Live tab: matches currently being recorded, reloaded on a background thread.
+pub struct LiveMatches {
+    pub replays: Vec<ReplayItem>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+    pub last_refresh: Option<Instant>,
+    /// Matches to download as soon as their recording has finished.
+    pub awaiting: Vec<ReplayItem>,
+    /// When each awaited match was queued, to give up on matches that never finish.
+    awaiting_since: HashMap<String, Instant>,
+    /// Finished matches waiting for the batch download to be free.
+    finished: Vec<String>,
+    tx: mpsc::Sender<LiveResponse>,
+    rx: mpsc::Receiver<LiveResponse>,
+}
+
+impl Default for LiveMatches {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            replays: Vec::new(),
+            is_loading: false,
+            error: None,
+            last_refresh: None,
+            awaiting: Vec::new(),
+            awaiting_since: HashMap::new(),
+            finished: Vec::new(),
+            tx,
+            rx,
+        }
+    }
+}
+
+impl LiveMatches {
+    pub fn is_awaiting(&self, replay_id: &str) -> bool {
+        self.awaiting.iter().any(|replay| replay.id == replay_id)
+    }
+}
+
+/// Replay shown in the details window, with its `/meta/` entry loaded in the background.
+pub struct ReplayDetails {
+    pub replay: ReplayItem,
+    /// `None` while the metadata is loading.
+    pub meta: Option<Result<MetaData, String>>,
+    rx: mpsc::Receiver<Result<MetaData, String>>,
+}
+
+/// State of the Players page.
+#[derive(Default)]
+pub struct PlayerBrowser {
+    pub filter: String,
+    pub sort: PlayerSort,
+    pub selected: Option<String>,
+    pub library: LibraryIndex,
+    /// Aggregated from `ReplayApp::seen_replays` and `library`, rebuilt when either changes.
+    pub players: Vec<PlayerStats>,
+    pub stale: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -156,9 +474,11 @@ pub struct ModInfo {
     pub failed: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Page {
     Main,
+    Live,
+    Players,
     ProcessLocal,
     Settings,
     Manage,
@@ -173,6 +493,43 @@ pub struct ReplayApp {
     pub show_completion_dialog: bool,
     pub current_page: Page,
     pub replay_list: ReplayListState,
+    pub batch: BatchDownloadState,
+    pub search: CatalogueSearch,
+    pub live: LiveMatches,
+    pub player_browser: PlayerBrowser,
+    pub details: Option<ReplayDetails>,
+    /// Every replay fetched this session, for the Players page.
+    pub seen_replays: HashMap<String, ReplayItem>,
+    pub watchlist_open: bool,
+    /// User ID and the address book entry being edited in the contact dialog.
+    pub contact_editor: Option<(String, Contact)>,
+    /// Watched replays already queued for an expiry download this session, so a failing
+    /// download is not retried every minute.
+    watchlist_attempted: HashSet<String>,
+    last_watchlist_check: Instant,
+    auto_download_running: bool,
+    /// A new listing page or a rule change still has to be checked against the rules.
+    auto_download_pending: bool,
+    /// Auto-download switch and rules the listing was last checked against, to notice
+    /// edits on the settings page.
+    planned_rules: (bool, Vec<AutoDownloadRule>),
+    /// Replays already handed to an auto-download rule this session.
+    auto_download_attempted: HashSet<String>,
+    /// Replays the rules and the script passed over. Cleared when either changes, so the
+    /// script only decides about each listed replay once.
+    auto_download_evaluated: HashSet<String>,
+    auto_download_tx: mpsc::Sender<AutoDownloadMessage>,
+    auto_download_rx: mpsc::Receiver<AutoDownloadMessage>,
+    pub script_host: ScriptHost,
+    last_script_check: Instant,
+    /// Tracked players already reported, so each sighting raises one alert.
+    tracking_seen: SeenSightings,
+    /// Errors writing tracking alerts to the log file or webhook.
+    tracking_error_tx: mpsc::Sender<String>,
+    tracking_error_rx: mpsc::Receiver<String>,
+    listing_request: u64,
+    listing_tx: mpsc::Sender<ListingResponse>,
+    listing_rx: mpsc::Receiver<ListingResponse>,
     profile_textures: HashMap<String, egui::TextureHandle>,
     loading_profiles: HashSet<String>,
     profile_tx: std::sync::mpsc::Sender<(String, egui::ColorImage)>,
@@ -180,12 +537,24 @@ pub struct ReplayApp {
     download_progress: Arc<Mutex<Option<DownloadProgress>>>,
     pub downloading_replay_id: Option<String>,
     pub downloaded_replays: HashSet<String>,
+    /// Replays in the download directory as of the last scan, newest first.
+    pub downloaded_files: Arc<Vec<DownloadedReplayInfo>>,
+    /// The download directory changed since the last scan started.
+    library_stale: bool,
+    library_scan_rx: Option<mpsc::Receiver<Vec<DownloadedReplayInfo>>>,
     downloaded_tx: DownloadedReplaysSender,
     downloaded_rx: DownloadedReplaysReceiver,
     pub settings: Settings,
     last_refresh_time: Instant,
     notifications: Vec<Notification>,
     next_notification_id: u64,
+    pub history: NotificationHistory,
+    pub log_viewer: LogViewer,
+    /// Card highlighted with the keyboard on the Replays page.
+    pub replay_cursor: ListCursor,
+    /// Row highlighted with the keyboard on the Manage page.
+    pub manage_cursor: ListCursor,
+    pub shortcuts_open: bool,
     update_info: Option<UpdateInfo>,
     update_rx: UpdateInfoReceiver,
     pub mod_info_cache: HashMap<String, ModInfo>,
@@ -204,8 +573,12 @@ impl ReplayApp {
         let (update_tx, update_rx) = std::sync::mpsc::channel();
         let (mod_info_tx, mod_info_rx) = std::sync::mpsc::channel();
         let (mod_thumbnail_tx, mod_thumbnail_rx) = mpsc::channel();
+        let (listing_tx, listing_rx) = mpsc::channel();
+        let (auto_download_tx, auto_download_rx) = mpsc::channel();
+        let (tracking_error_tx, tracking_error_rx) = mpsc::channel();
 
         let settings = Self::load_settings().unwrap_or_default();
+        let network_error = http_client::configure(&settings.network).err();
 
         let mut app = Self {
             progress: Arc::new(Mutex::new(None)),
@@ -216,6 +589,31 @@ impl ReplayApp {
             show_completion_dialog: false,
             current_page: Page::Main,
             replay_list: ReplayListState::default(),
+            batch: BatchDownloadState::default(),
+            search: CatalogueSearch::default(),
+            live: LiveMatches::default(),
+            player_browser: PlayerBrowser::default(),
+            details: None,
+            seen_replays: HashMap::new(),
+            watchlist_open: false,
+            contact_editor: None,
+            watchlist_attempted: HashSet::new(),
+            last_watchlist_check: Instant::now(),
+            auto_download_running: false,
+            auto_download_pending: false,
+            planned_rules: (false, Vec::new()),
+            auto_download_attempted: HashSet::new(),
+            auto_download_evaluated: HashSet::new(),
+            auto_download_tx,
+            auto_download_rx,
+            script_host: ScriptHost::default(),
+            last_script_check: Instant::now() - Duration::from_secs(60),
+            tracking_seen: SeenSightings::load(),
+            tracking_error_tx,
+            tracking_error_rx,
+            listing_request: 0,
+            listing_tx,
+            listing_rx,
             profile_textures: HashMap::new(),
             loading_profiles: HashSet::new(),
             profile_tx,
@@ -223,12 +621,20 @@ impl ReplayApp {
             download_progress: Arc::new(Mutex::new(None)),
             downloading_replay_id: None,
             downloaded_replays: HashSet::new(),
+            downloaded_files: Arc::new(Vec::new()),
+            library_stale: true,
+            library_scan_rx: None,
             downloaded_tx,
             downloaded_rx,
             settings,
             last_refresh_time: Instant::now(),
             notifications: Vec::new(),
             next_notification_id: 0,
+            history: NotificationHistory::default(),
+            log_viewer: LogViewer::default(),
+            replay_cursor: ListCursor::default(),
+            manage_cursor: ListCursor::default(),
+            shortcuts_open: false,
             update_info: None,
             update_rx,
             mod_info_cache: HashMap::new(),
@@ -239,38 +645,40 @@ impl ReplayApp {
             mod_thumbnail_rx,
             mod_thumbnail_tx,
         };
+        if let Some(e) = network_error {
+            app.show_error(format!("Invalid network settings: {}", e));
+        }
         app.refresh_replays();
-        app.check_downloaded_replays();
 
         // Start update check
         let update_tx_clone = update_tx.clone();
         thread::spawn(move || {
             let current_version = env!("CARGO_PKG_VERSION");
-            
-            let client = match Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build() {
-                    Ok(client) => client,
-                    Err(_) => return,
-                };
-                
             let url = "https://api.github.com/repos/cikeZ00/PavlovReplayToolbox/releases/latest";
             
-            let response = match client.get(url)
-                .header("User-Agent", "PavlovReplayToolbox")
+            let response = match http_client::client()
+                .get(url)
+                .timeout(INTERACTIVE_TIMEOUT)
                 .send() {
                     Ok(resp) => {
                         if !resp.status().is_success() {
+                            log::warn!("Update check failed: HTTP {}", resp.status());
                             return;
                         }
                         resp
                     },
-                    Err(_) => return,
+                    Err(e) => {
+                        log::warn!("Update check failed: {}", e);
+                        return;
+                    }
                 };
                 
             let github_release: GitHubRelease = match response.json() {
                 Ok(release) => release,
-                Err(_) => return,
+                Err(e) => {
+                    log::warn!("Update check returned an invalid release: {}", e);
+                    return;
+                }
             };
             
             // Remove 'v' prefix if present
@@ -303,6 +711,7 @@ impl ReplayApp {
                 current_version != latest_version
             };
             
+            log::info!("Latest release is {}, running {}", latest_version, current_version);
             if update_available {
                 let update_info = UpdateInfo {
                     current_version: current_version.to_string(),
@@ -330,27 +739,15 @@ impl ReplayApp {
     fn load_profile(&mut self, user: String) {
         self.loading_profiles.insert(user.clone());
         let profile_tx = self.profile_tx.clone();
-        let status_clone = Arc::clone(&self.status);
         
         thread::spawn(move || {
-            let client = match Client::builder()
-                .timeout(Some(Duration::from_secs(10)))
-                .build() {
-                    Ok(client) => client,
-                    Err(e) => {
-                        if let Ok(mut status) = status_clone.lock() {
-                            *status = format!("Failed to initialize HTTP client for profile: {}", e);
-                        }
-                        return;
-                    }
-                };
-                
             let url = format!("http://prod.cdn.pavlov-vr.com/avatar/{}.png", user);
             
-            match client.get(&url).send() {
+            // Avatars are optional; failures only leave the placeholder in place.
+            match http_client::client().get(&url).timeout(INTERACTIVE_TIMEOUT).send() {
                 Ok(response) => {
                     if !response.status().is_success() {
-                        // Profile not found or server error, but we can silently fail
+                        log::debug!("No avatar for user {}: HTTP {}", user, response.status());
                         return;
                     }
                     
@@ -363,142 +760,330 @@ impl ReplayApp {
                                     let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &img.into_raw());
                                     let _ = profile_tx.send((user, color_image));
                                 },
-                                Err(_) => {
-                                    // Invalid image data, can silently fail
-                                }
+                                Err(e) => log::warn!("Invalid avatar image for user {}: {}", user, e),
                             }
                         },
-                        Err(_) => {
-                            // Failed to get bytes, can silently fail
-                        }
+                        Err(e) => log::warn!("Failed to download avatar for user {}: {}", user, e),
                     }
                 },
-                Err(e) => {
-                    if e.is_timeout() || e.is_connect() {
-                        // Connection issues, can silently fail
-                        return;
-                    }
-                }
+                Err(e) => log::warn!("Failed to load avatar for user {}: {}", user, e),
             }
         });
     }
 
-    fn fetch_replays(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let client = match Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build() {
-                Ok(client) => client,
-                Err(e) => return Err(format!("Failed to initialize HTTP client: {}", e).into())
-            };
+    /// Fetches the current page on a background thread. The result is picked up by
+    /// `check_listing`; a newer refresh supersedes any fetch still in flight.
+    pub fn refresh_replays(&mut self) {
+        if let Ok(mut status) = self.status.lock() {
+            *status = "Loading replays...".to_string();
+        }
 
-        let offset = self.replay_list.current_page * 100;
-        
-        let mut url = format!(
-            "{}/find/?game=all&offset={}&live=false",
-            API_BASE_URL, offset
-        );
-        
-        match self.replay_list.filters.platform {
-            PlatformFilter::Quest => url.push_str("&shack=true"),
-            PlatformFilter::PC => url.push_str("&shack=false"),
-            PlatformFilter::All => {} 
-        }
-
-        let response = match client.get(&url).send() {
-            Ok(resp) => {
-                if !resp.status().is_success() {
-                    return Err(format!("Server returned error status: {} - {}", 
-                        resp.status().as_u16(), 
-                        resp.status().canonical_reason().unwrap_or("Unknown error")).into());
+        self.listing_request += 1;
+        self.replay_list.is_loading = true;
+        let request = self.listing_request;
+        let query = self.replay_list.filters.query(self.replay_list.current_page * PAGE_SIZE, false);
+        let listing_tx = self.listing_tx.clone();
+
+        thread::spawn(move || {
+            let result = fetch_page(&query)
+                .map(|response| {
+                    let replays = response.replays.into_iter().map(ReplayItem::from).collect();
+                    (replays, total_pages(response.total))
+                })
+                .map_err(|e| e.to_string());
+            let _ = listing_tx.send(ListingResponse { request, result });
+        });
+    }
+
+    fn check_listing(&mut self) {
+        while let Ok(response) = self.listing_rx.try_recv() {
+            if response.request != self.listing_request {
+                continue;
+            }
+
+            self.replay_list.is_loading = false;
+            self.last_refresh_time = Instant::now();
+            match response.result {
+                Ok((replays, total_pages)) => {
+                    if update_expiry(&mut self.settings.watchlist, &replays) {
+                        if let Err(err) = self.save_settings() {
+                            self.show_error(format!("Error saving settings: {}", err));
+                        }
+                    }
+                    // Only the newest page counts as new matches.
+                    if self.replay_list.current_page == 0 {
+                        self.report_tracked_players(&replays);
+                    }
+                    self.remember_replays(&replays);
+                    self.replay_list.replays = replays;
+                    self.replay_list.total_pages = total_pages;
+                    if let Ok(mut status) = self.status.lock() {
+                        *status = "Replays loaded successfully".to_string();
+                    }
+                    self.show_success("Replays loaded successfully");
+
+                    // Rules run on every listing, whichever page is open.
+                    self.auto_download_pending = true;
+                    if !self.is_downloading {
+                        self.check_auto_download_triggers();
+                    }
                 }
-                resp
-            },
-            Err(e) => {
-                return if e.is_timeout() {
-                    Err("Connection timed out. Server may be down or unreachable.".into())
-                } else if e.is_connect() {
-                    Err("Failed to connect to server. Please check your internet connection.".into())
-                } else {
-                    Err(format!("Network error: {}", e).into())
+                Err(e) => {
+                    let error_message = format!("Error loading replays: {}", e);
+                    if let Ok(mut status) = self.status.lock() {
+                        *status = error_message.clone();
+                    }
+                    self.show_error(error_message);
                 }
             }
+        }
+    }
+    
+    /// Searches every page of the catalogue for replays matching the current filters.
+    /// Matches are streamed into `self.search` as each page arrives.
+    pub fn start_catalogue_search(&mut self) {
+        self.stop_catalogue_search();
+
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.search = CatalogueSearch {
+            active: true,
+            running: true,
+            cancel: Arc::clone(&cancel),
+            rx: Some(rx),
+            ..CatalogueSearch::default()
         };
 
-        let api_response = match response.json::<ApiResponse>() {
-            Ok(data) => data,
-            Err(e) => return Err(format!("Failed to parse server response: {}. The API may have changed format.", e).into())
+        let filters = self.replay_list.filters.clone();
+        let book = self.settings.address_book.clone();
+        thread::spawn(move || {
+            let result = search_all_pages(&filters, &book, false, |matches, scanned, total| {
+                if cancel.load(Ordering::Relaxed) {
+                    return false;
+                }
+                let replays = matches.into_iter().map(ReplayItem::from).collect();
+                tx.send(SearchEvent::Matches { replays, scanned, total: total.max(0) as usize }).is_ok()
+            });
+            let _ = tx.send(match result {
+                Ok(()) => SearchEvent::Finished,
+                Err(e) => SearchEvent::Failed(e.to_string()),
+            });
+        });
+    }
+
+    /// Stops a running search, keeping the results found so far.
+    pub fn stop_catalogue_search(&mut self) {
+        self.search.cancel.store(true, Ordering::Relaxed);
+        self.search.running = false;
+        self.search.rx = None;
+    }
+
+    /// Leaves search mode and goes back to the paged listing.
+    pub fn clear_catalogue_search(&mut self) {
+        self.stop_catalogue_search();
+        self.search = CatalogueSearch::default();
+    }
+
+    fn check_catalogue_search(&mut self) {
+        let Some(rx) = &self.search.rx else {
+            return;
         };
 
-        self.replay_list.total_pages = (api_response.total as f32 / 100.0).ceil() as usize;
-        self.replay_list.replays = api_response
-            .replays
-            .into_iter()
-            .map(|r| ReplayItem {
-                id: r.id,
-                game_mode: r.game_mode,
-                map_name: r.map_name,
-                created_date: r.created,
-                time_since: r.time_since,
-                shack: r.shack,
-                modcount: r.modcount,
-                competitive: r.competitive,
-                workshop_mods: r.workshop_mods,
-                live: r.live,
-                users: r.users.unwrap_or_default(),
-            })
-            .collect();
-        Ok(())
+        let mut outcome = None;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                SearchEvent::Matches { replays, scanned, total } => {
+                    for replay in &replays {
+                        self.seen_replays.insert(replay.id.clone(), replay.clone());
+                    }
+                    self.player_browser.stale = true;
+                    self.search.results.extend(replays);
+                    self.search.scanned = scanned;
+                    self.search.total = total;
+                }
+                SearchEvent::Finished => outcome = Some(Ok(())),
+                SearchEvent::Failed(e) => outcome = Some(Err(e)),
+            }
+        }
+
+        match outcome {
+            Some(Ok(())) => {
+                self.search.running = false;
+                self.search.rx = None;
+                self.show_success(format!("Search finished: {} matching replays", self.search.results.len()));
+            }
+            Some(Err(e)) => {
+                self.search.running = false;
+                self.search.rx = None;
+                self.show_error(format!("Search failed: {}", e));
+            }
+            None => {}
+        }
     }
 
-    pub fn refresh_replays(&mut self) {
+    /// Downloads every listed replay selected by an auto-download rule into the rule's folder.
+    /// Checks the current page against the rules when a listing arrives or the rules change.
+    /// A page arriving while downloads run is checked once they finish.
+    fn check_auto_download_triggers(&mut self) {
+        if self.auto_download_running {
+            return;
+        }
+        self.auto_download_pending = false;
+        if !self.settings.has_active_rules() {
+            return;
+        }
+
+        let candidates: Vec<ApiReplay> = self.replay_list.replays.iter()
+            .filter(|replay| !self.downloaded_replays.contains(&replay.id))
+            .filter(|replay| !self.auto_download_attempted.contains(&replay.id) && !self.auto_download_evaluated.contains(&replay.id))
+            .map(ApiReplay::from)
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+        self.auto_download_evaluated.extend(candidates.iter().map(|replay| replay.id.clone()));
+
+        let mut plan = plan_downloads(
+            &self.settings.auto_download_rules,
+            self.script_host.script(),
+            &self.settings.download_dir,
+            candidates,
+        );
+        // One toast per listing, the individual errors are kept in the history.
+        if let Some((_, first)) = plan.script_errors.first() {
+            let summary = match plan.script_errors.len() {
+                1 => first.clone(),
+                count => format!("Download script failed on {} replays: {}", count, first),
+            };
+            self.show_error(summary);
+        }
+        for (index, (id, error)) in plan.script_errors.drain(..).enumerate() {
+            if index > 0 {
+                self.history.push(NotificationType::Error, false, error);
+            }
+            self.auto_download_attempted.insert(id);
+        }
+        if plan.jobs.is_empty() {
+            return;
+        }
+
+        let count = plan.len();
+        let jobs = plan.jobs;
+        let file_names = plan.file_names;
+        self.auto_download_attempted.extend(jobs.iter().flat_map(|(_, replays)| replays.iter().map(|replay| replay.id.clone())));
+        self.auto_download_running = true;
         if let Ok(mut status) = self.status.lock() {
-            *status = "Loading replays...".to_string();
+            *status = format!("Auto-downloading {} replays", count);
         }
+        self.show_info(format!("Auto-downloading {} replays", count));
 
-        match self.fetch_replays() {
-            Ok(_) => {
-                if let Ok(mut status) = self.status.lock() {
-                    *status = "Replays loaded successfully".to_string();
+        let naming = self.settings.naming.clone();
+        let tx = self.auto_download_tx.clone();
+        thread::spawn(move || {
+            let (mut downloaded, mut skipped, mut failed) = (0, 0, 0);
+            for (download_dir, replays) in jobs {
+                // Rule folders may be outside the download directory scanned at startup.
+                let existing = scan_downloaded_ids(&download_dir, naming.folder_depth());
+                let (replays, existing): (Vec<ApiReplay>, Vec<ApiReplay>) = replays
+                    .into_iter()
+                    .partition(|replay| !existing.contains(&replay.id));
+                for replay in existing {
+                    skipped += 1;
+                    let _ = tx.send(AutoDownloadMessage::Skipped(replay.id));
                 }
-                self.show_success("Replays loaded successfully");
-                self.last_refresh_time = Instant::now();
-                
-                // Check for auto-download triggers after refreshing
-                self.check_auto_download_triggers();
-            }
-            Err(e) => {
-                let error_message = format!("Error loading replays: {}", e);
-                if let Ok(mut status) = self.status.lock() {
-                    *status = error_message.clone();
+
+                let ids: Vec<String> = replays.iter().map(|replay| replay.id.clone()).collect();
+                let options = BatchOptions {
+                    download_dir,
+                    naming: naming.clone(),
+                    jobs: DEFAULT_JOBS,
+                    skip_existing: true,
+                    file_names: file_names.clone(),
+                };
+                match download_listed(replays, &options, &|_| {}) {
+                    Ok(summary) => {
+                        downloaded += summary.downloaded.len();
+                        skipped += summary.skipped.len();
+                        failed += summary.failed.len();
+                        for (id, _) in summary.downloaded {
+                            let _ = tx.send(AutoDownloadMessage::Downloaded(id));
+                        }
+                        for id in summary.skipped {
+                            let _ = tx.send(AutoDownloadMessage::Skipped(id));
+                        }
+                        for (id, error) in summary.failed {
+                            let _ = tx.send(AutoDownloadMessage::Failed { id, error: error.to_string() });
+                        }
+                    }
+                    Err(e) => {
+                        failed += ids.len();
+                        for id in ids {
+                            let _ = tx.send(AutoDownloadMessage::Failed { id, error: e.to_string() });
+                        }
+                    }
                 }
-                self.show_error(error_message);
             }
+            let _ = tx.send(AutoDownloadMessage::Finished { downloaded, skipped, failed });
+        });
+    }
+
+    /// Checks the listing again when auto-download was switched on or its rules were edited.
+    fn check_auto_download_rules(&mut self) {
+        if self.settings.auto_download_enabled != self.planned_rules.0
+            || self.settings.auto_download_rules != self.planned_rules.1
+        {
+            self.planned_rules = (self.settings.auto_download_enabled, self.settings.auto_download_rules.clone());
+            self.auto_download_evaluated.clear();
+            self.auto_download_pending = true;
         }
     }
-    
-    fn check_auto_download_triggers(&mut self) {
-        if !self.settings.auto_download_enabled || 
-           self.settings.auto_download_trigger_user_id.is_empty() ||
-           self.is_downloading {
+
+    /// Loads the download script from settings and reloads it when the file changes.
+    fn check_download_script(&mut self) {
+        if self.last_script_check.elapsed() < Duration::from_secs(2) {
             return;
         }
-    
-        let trigger_user_id = self.settings.auto_download_trigger_user_id.to_lowercase();
-        
-        let replay_to_download = self.replay_list.replays.iter()
-            .find(|replay| {
-                !self.downloaded_replays.contains(&replay.id) && 
-                replay.users.iter().any(|user| user.to_lowercase().contains(&trigger_user_id))
-            })
-            .map(|replay| replay.id.clone());
-        
-        if let Some(replay_id) = replay_to_download {
-            if let Ok(mut status) = self.status.lock() {
-                *status = format!("Auto-downloading replay with user ID: {}", 
-                                 self.settings.auto_download_trigger_user_id);
+        self.last_script_check = Instant::now();
+
+        match self.script_host.refresh(&self.settings.auto_download_script) {
+            Some(Ok(())) => {
+                // Replays the previous version skipped or failed on get another chance.
+                self.auto_download_attempted.clear();
+                self.auto_download_evaluated.clear();
+                self.auto_download_pending = true;
+                self.show_info(format!("Loaded download script {}", self.settings.auto_download_script.display()));
+            }
+            Some(Err(e)) => self.show_error(e),
+            None => {}
+        }
+    }
+
+    fn check_auto_downloads(&mut self) {
+        while let Ok(message) = self.auto_download_rx.try_recv() {
+            match message {
+                AutoDownloadMessage::Downloaded(id) | AutoDownloadMessage::Skipped(id) => {
+                    self.downloaded_replays.insert(id);
+                }
+                AutoDownloadMessage::Failed { id, error } => {
+                    // Only the summary is shown, the reasons are kept in the history.
+                    self.history.push(NotificationType::Error, false, format!("Auto-download of {} failed: {}", id, error));
+                }
+                AutoDownloadMessage::Finished { downloaded, skipped, failed } => {
+                    self.auto_download_running = false;
+                    if downloaded > 0 {
+                        self.rescan_library();
+                    }
+                    let message = format!(
+                        "Auto-download finished: {} downloaded, {} already present, {} failed",
+                        downloaded, skipped, failed
+                    );
+                    if failed == 0 {
+                        self.show_success(message);
+                    } else {
+                        self.show_warning(message);
+                    }
+                }
             }
-            
-            self.process_online_replay(&replay_id);
         }
     }
 
@@ -522,16 +1107,10 @@ impl ReplayApp {
 
         let progress_clone = Arc::clone(&self.progress);
         let status_clone = Arc::clone(&self.status);
-        let path_clone = self.selected_path.clone().unwrap();
+        let output_dir = self.selected_path.clone().unwrap();
+        let naming = self.settings.naming.clone();
 
         thread::spawn(move || {
-            if let Err(e) = std::env::set_current_dir(&path_clone) {
-                if let Ok(mut status) = status_clone.lock() {
-                    *status = format!("Error: Failed to set working directory - {}", e);
-                }
-                return;
-            }
-
             let config = Config {
                 update_callback: Box::new(move |progress| {
                     if let Ok(mut lock) = progress_clone.lock() {
@@ -541,7 +1120,11 @@ impl ReplayApp {
                 ..Default::default()
             };
 
-            let result = process_replay(Some(config));
+            let result = process_replay(&replay_chunks_dir(), Some(config)).and_then(|replay| {
+                let filename = local_replay_filename(&replay.meta, &replay.data, &naming).map_err(|e| e.to_string())?;
+                write_replay_file(&output_dir.join(filename), &replay.data)?;
+                Ok(())
+            });
 
             if let Ok(mut status) = status_clone.lock() {
                 *status = match result {
@@ -562,22 +1145,13 @@ impl ReplayApp {
         let progress_clone = Arc::clone(&self.download_progress);
         let downloaded_tx = self.downloaded_tx.clone();
         let download_dir = self.settings.download_dir.clone();
+        let naming = self.settings.naming.clone();
 
         thread::spawn(move || {
             if let Ok(mut status) = status_clone.lock() {
                 *status = "Downloading replay...".to_string();
             }
 
-            let client = match Client::builder().build() {
-                Ok(client) => client,
-                Err(e) => {
-                    if let Ok(mut status) = status_clone.lock() {
-                        *status = format!("Failed to initialize HTTP client: {}", e);
-                    }
-                    return;
-                }
-            };
-
             // Initialize progress tracking
             if let Ok(mut progress) = progress_clone.lock() {
                 *progress = Some(DownloadProgress::default());
@@ -595,8 +1169,8 @@ impl ReplayApp {
                 }) as Box<dyn Fn(usize, usize) + Send + Sync>
             };
 
-            let result: Result<(), Box<dyn std::error::Error>> = (|| {
-                let replay_data = match download_replay(&replay_id_clone, Some(download_progress_callback)) {
+            let result: Result<(), Box<dyn std::error::Error + Send + Sync>> = (|| {
+                let replay = match download_replay(&replay_id_clone, Some(download_progress_callback)) {
                     Ok(data) => data,
                     Err(e) => return Err(format!("Failed to download replay data: {}", e).into())
                 };
@@ -612,82 +1186,21 @@ impl ReplayApp {
 
                 update_build_progress(0, 100);
 
-                let metadata_result = match client
-                    .get(&format!("{}/meta/{}", API_BASE_URL, replay_id_clone))
-                    .send() {
-                        Ok(resp) => {
-                            update_build_progress(10, 100);
-                            
-                            if !resp.status().is_success() {
-                                return Err(format!(
-                                    "Failed to fetch replay metadata: Server returned {} - {}", 
-                                    resp.status().as_u16(),
-                                    resp.status().canonical_reason().unwrap_or("Unknown error")
-                                ).into());
-                            }
-                            
-                            match resp.json::<MetaData>() {
-                                Ok(data) => {
-                                    update_build_progress(20, 100);
-                                    data
-                                },
-                                Err(e) => return Err(format!(
-                                    "Failed to parse replay metadata: {}. The API format may have changed.", e
-                                ).into())
-                            }
-                        },
-                        Err(e) => {
-                            return if e.is_timeout() {
-                                Err("Connection timed out while fetching replay metadata.".into())
-                            } else if e.is_connect() {
-                                Err("Failed to connect to metadata server. Please check your internet connection.".into())
-                            } else {
-                                Err(format!("Network error retrieving metadata: {}", e).into())
-                            }
-                        }
-                    };
-
-                update_build_progress(30, 100);
-
-                let created_datetime = match chrono::DateTime::parse_from_rfc3339(&metadata_result.created)
-                    .or_else(|_| -> Result<_, Box<dyn std::error::Error>> {
-                        let ts = metadata_result.created
-                            .parse::<i64>()
-                            .map_err(|e| format!("Invalid timestamp format: {}", e))?;
-                        chrono::DateTime::from_timestamp(ts, 0)
-                            .map(|dt| dt.fixed_offset())
-                            .ok_or_else(|| "Invalid timestamp".into())
-                    }) {
-                        Ok(dt) => {
-                            update_build_progress(40, 100);
-                            dt
-                        },
-                        Err(e) => return Err(format!("Failed to parse replay date: {}", e).into())
-                    };
+                let filename = replay_filename(&replay.meta, &replay.info, &naming)?;
 
                 update_build_progress(50, 100);
-
-                let formatted_date = created_datetime.format("%Y.%m.%d-%H.%M.%S");
-                let sanitized_name = metadata_result.friendly_name.replace([' ','<','>',':','"','/',',','\\','?','*','='], "-");
-                let filename = format!(
-                    "{}-{}-{}({}).replay",
-                    sanitized_name,
-                    metadata_result.game_mode,
-                    formatted_date,
-                    replay_id_clone
-                );
-
-                update_build_progress(75, 100);
                 
-                let output_path = download_dir.join(filename);
                 update_build_progress(90, 100);
                 
-                match fs::write(output_path, replay_data) {
-                    Ok(_) => {
+                let output_path = match write_downloaded_replay(&download_dir.join(filename), &replay.info.id, &replay.data) {
+                    Ok(path) => {
                         update_build_progress(100, 100);
+                        path
                     },
                     Err(e) => return Err(format!("Failed to save replay file: {}", e).into())
-                }
+                };
+                // Without an index entry the ID is guessed from the file name, so a failure does not fail the download.
+                let _ = record_download(&replay.info, &output_path);
 
                 let _ = downloaded_tx.send(replay_id_clone);
 
@@ -710,44 +1223,380 @@ impl ReplayApp {
         });
     }
 
-    fn check_downloaded_replays(&mut self) {
-        if let Ok(entries) = fs::read_dir(std::env::current_dir().unwrap_or_default()) {
-            for entry in entries.flatten() {
-                if let Ok(file_type) = entry.file_type() {
-                    if file_type.is_file() {
-                        if let Some(ext) = entry.path().extension() {
-                            if ext == "replay" {
-                                if let Some(filename) = entry.path().file_name() {
-                                    if let Some(filename_str) = filename.to_str() {
-                                        if let Some(id_start) = filename_str.rfind('(') {
-                                            if let Some(id_end) = filename_str[id_start..].find(')') {
-                                                let id = &filename_str[id_start + 1..id_start + id_end];
-                                                self.downloaded_replays.insert(id.to_string());
-                                                continue;
-                                            }
-                                        }
-
-                                        if let Ok(mut file) = fs::File::open(entry.path()) {
-                                            let mut buffer = [0; 1024];
-                                            if file.read(&mut buffer).is_ok() {
-                                                let content = String::from_utf8_lossy(&buffer);
-                                                if let Some(id_start) = content.find("\"id\":\"") {
-                                                    let id_start = id_start + 6;
-                                                    if let Some(id_end) = content[id_start..].find('"') {
-                                                        let id = &content[id_start..id_start + id_end];
-                                                        self.downloaded_replays.insert(id.to_string());
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
+    /// Opens the batch dialog with `ids` and starts downloading them.
+    pub fn queue_downloads(&mut self, ids: &[String]) {
+        if self.batch.running {
+            self.show_warning("A batch download is already running");
+            return;
+        }
+        self.batch.ids_text = ids.join("\n");
+        self.batch.open = true;
+        self.start_batch_download();
+    }
+
+    /// Starts downloading every ID in the batch dialog on a background thread.
+    pub fn start_batch_download(&mut self) {
+        let ids = parse_replay_ids(&self.batch.ids_text);
+        if ids.is_empty() {
+            self.show_warning("Enter at least one replay ID");
+            return;
+        }
+        self.start_batch(ids);
+    }
+
+    /// Downloads `ids` on a background thread, shown in the batch dialog without touching
+    /// the IDs typed there.
+    fn start_batch(&mut self, ids: Vec<String>) {
+        self.batch.running = true;
+        if let Ok(mut entries) = self.batch.entries.lock() {
+            *entries = ids.iter().map(|id| (id.clone(), BatchEntryStatus::Queued)).collect();
+        }
+        self.show_info(format!("Downloading {} replays", ids.len()));
+
+        let options = BatchOptions {
+            download_dir: self.settings.download_dir.clone(),
+            naming: self.settings.naming.clone(),
+            jobs: self.batch.jobs,
+            skip_existing: true,
+            file_names: HashMap::new(),
+        };
+        let entries = Arc::clone(&self.batch.entries);
+        let result = Arc::clone(&self.batch.result);
+
+        thread::spawn(move || {
+            let on_event = |event: BatchEvent| {
+                let (id, status) = match event {
+                    BatchEvent::Skipped { id, reason } => (id, BatchEntryStatus::Skipped(reason)),
+                    BatchEvent::Started { id } => (id, BatchEntryStatus::Downloading { current: 0, total: 0 }),
+                    BatchEvent::Progress { id, current, total } => (id, BatchEntryStatus::Downloading { current, total }),
+                    BatchEvent::Metadata { .. } => return,
+                    BatchEvent::Finished { id, path, .. } => (id, BatchEntryStatus::Done(path)),
+                    BatchEvent::Failed { id, error } => (id, BatchEntryStatus::Failed(error.to_string())),
+                };
+                if let Ok(mut entries) = entries.lock() {
+                    if let Some(entry) = entries.iter_mut().find(|(entry_id, _)| *entry_id == id) {
+                        entry.1 = status;
                     }
                 }
+            };
+
+            let summary = run_batch(&ids, &options, &on_event).map_err(|e| e.to_string());
+            if let Ok(mut result) = result.lock() {
+                *result = Some(summary);
+            }
+        });
+    }
+
+    /// Reloads the live listing on a background thread and checks whether awaited matches
+    /// that left it have finished recording.
+    pub fn refresh_live_matches(&mut self) {
+        if self.live.is_loading {
+            return;
+        }
+        self.live.is_loading = true;
+        let awaiting: Vec<String> = self.live.awaiting.iter().map(|replay| replay.id.clone()).collect();
+        let tx = self.live.tx.clone();
+
+        thread::spawn(move || {
+            let result = fetch_live_replays()
+                .map(|replays| replays.into_iter().map(ReplayItem::from).collect::<Vec<_>>())
+                .map_err(|e| e.to_string());
+            // A match still in the live listing is still being recorded. Errors are retried
+            // on the next refresh.
+            let recorded = match &result {
+                Ok(live) => awaiting
+                    .into_iter()
+                    .filter(|id| !live.iter().any(|replay| &replay.id == id))
+                    .filter(|id| is_recorded(id).unwrap_or(false))
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
+            let _ = tx.send(LiveResponse { result, recorded });
+        });
+    }
+
+    /// Queues `replay` for download once its recording has finished.
+    pub fn download_when_finished(&mut self, replay: &ReplayItem) {
+        if !self.live.is_awaiting(&replay.id) {
+            self.live.awaiting.push(replay.clone());
+            self.live.awaiting_since.insert(replay.id.clone(), Instant::now());
+            self.show_info(format!("{} will be downloaded when the match ends", replay.map_name));
+        }
+    }
+
+    pub fn cancel_download_when_finished(&mut self, replay_id: &str) {
+        self.live.awaiting.retain(|replay| replay.id != replay_id);
+        self.live.awaiting_since.remove(replay_id);
+    }
+
+    fn check_live_matches(&mut self) {
+        while let Ok(response) = self.live.rx.try_recv() {
+            self.live.is_loading = false;
+            self.live.last_refresh = Some(Instant::now());
+            match response.result {
+                Ok(replays) => {
+                    self.report_tracked_players(&replays);
+                    self.remember_replays(&replays);
+                    self.live.replays = replays;
+                    self.live.error = None;
+                }
+                Err(e) => self.live.error = Some(e),
+            }
+
+            for id in response.recorded {
+                let Some(index) = self.live.awaiting.iter().position(|replay| replay.id == id) else {
+                    continue;
+                };
+                let replay = self.live.awaiting.remove(index);
+                self.live.awaiting_since.remove(&id);
+                self.show_success(format!("Match on {} finished", replay.map_name));
+                self.live.finished.push(id);
             }
         }
+
+        let expired: Vec<ReplayItem> = self.live.awaiting.iter()
+            .filter(|replay| self.live.awaiting_since.get(&replay.id).is_some_and(|since| since.elapsed() >= LIVE_WAIT_LIMIT))
+            .cloned()
+            .collect();
+        for replay in expired {
+            self.cancel_download_when_finished(&replay.id);
+            self.show_warning(format!(
+                "Stopped waiting for the match on {}, it did not finish recording within {} hours",
+                replay.map_name,
+                LIVE_WAIT_LIMIT.as_secs() / 3600
+            ));
+        }
+
+        if !self.live.finished.is_empty() && !self.batch.running {
+            let finished = std::mem::take(&mut self.live.finished);
+            self.start_batch(finished);
+        }
+
+        let due = self.live.last_refresh.is_none_or(|last| last.elapsed() >= LIVE_REFRESH_INTERVAL);
+        if due && (self.current_page == Page::Live || !self.live.awaiting.is_empty()) {
+            self.refresh_live_matches();
+        }
+    }
+
+    /// Alerts about tracked players in `replays` that were not reported yet, and writes the
+    /// alerts to the log file and webhook on a background thread.
+    fn report_tracked_players(&mut self, replays: &[ReplayItem]) {
+        // Players without a tracking nickname are named after their address book entry.
+        let tracked: Vec<TrackedPlayer> = self.settings.tracked_players.iter()
+            .map(|player| {
+                let mut player = player.clone();
+                if player.nickname.is_empty() {
+                    player.nickname = self.settings.address_book.get(&player.user_id)
+                        .map(Contact::label)
+                        .unwrap_or_default();
+                }
+                player
+            })
+            .collect();
+        let sightings = find_sightings(&tracked, replays, &mut self.tracking_seen);
+        if sightings.is_empty() {
+            return;
+        }
+        if let Err(err) = self.tracking_seen.save() {
+            self.show_error(format!("Error saving reported sightings: {}", err));
+        }
+        for sighting in &sightings {
+            self.show_info(sighting.message());
+        }
+
+        let log_file = self.settings.tracking_log_file.clone();
+        let webhook_url = self.settings.tracking_webhook_url.trim().to_string();
+        if log_file.as_os_str().is_empty() && webhook_url.is_empty() {
+            return;
+        }
+        let error_tx = self.tracking_error_tx.clone();
+        thread::spawn(move || {
+            if !log_file.as_os_str().is_empty() {
+                if let Err(e) = append_to_log(&log_file, &sightings) {
+                    let _ = error_tx.send(format!("Failed to write tracking log: {}", e));
+                }
+            }
+            if !webhook_url.is_empty() {
+                if let Err(e) = send_webhook(&webhook_url, &sightings) {
+                    let _ = error_tx.send(format!("Failed to send tracking webhook: {}", e));
+                }
+            }
+        });
+    }
+
+    fn check_tracking_errors(&mut self) {
+        while let Ok(error) = self.tracking_error_rx.try_recv() {
+            self.show_error(error);
+        }
+    }
+
+    /// Adds `user_id` to the tracked players, or removes it if it is already tracked.
+    pub fn toggle_tracked_player(&mut self, user_id: &str) {
+        if is_tracked(&self.settings.tracked_players, user_id) {
+            self.settings.tracked_players.retain(|player| !player.user_id.eq_ignore_ascii_case(user_id));
+            self.show_info(format!("Stopped tracking {}", user_id));
+        } else {
+            self.settings.tracked_players.push(TrackedPlayer { user_id: user_id.to_string(), nickname: String::new() });
+            self.show_success(format!("Tracking {}", user_id));
+        }
+        if let Err(err) = self.save_settings() {
+            self.show_error(format!("Error saving settings: {}", err));
+        }
+    }
+
+    fn remember_replays(&mut self, replays: &[ReplayItem]) {
+        for replay in replays {
+            self.seen_replays.insert(replay.id.clone(), replay.clone());
+        }
+        self.player_browser.stale = true;
+    }
+
+    /// Reloads the library index and rebuilds the player list.
+    pub fn reload_player_library(&mut self) {
+        self.player_browser.library = LibraryIndex::load();
+        self.player_browser.stale = true;
+    }
+
+    /// Rebuilds the aggregated player list if new replays were fetched or downloaded.
+    pub fn update_players(&mut self) {
+        if !self.player_browser.stale {
+            return;
+        }
+        let mut players = aggregate_players(self.seen_replays.values(), &self.player_browser.library);
+        self.player_browser.sort.apply(&mut players, &self.settings.address_book);
+        self.player_browser.players = players;
+        self.player_browser.stale = false;
+    }
+
+    /// Switches to `page`, loading whatever it shows.
+    pub fn open_page(&mut self, page: Page) {
+        if page == Page::Players {
+            self.reload_player_library();
+        }
+        self.current_page = page;
+    }
+
+    /// Opens the details window for `replay` and fetches its metadata.
+    pub fn open_replay_details(&mut self, replay: &ReplayItem) {
+        let (tx, rx) = mpsc::channel();
+        let replay_id = replay.id.clone();
+        thread::spawn(move || {
+            let _ = tx.send(fetch_metadata(&replay_id).map_err(|e| e.to_string()));
+        });
+        self.details = Some(ReplayDetails { replay: replay.clone(), meta: None, rx });
+    }
+
+    fn check_replay_details(&mut self) {
+        if let Some(details) = self.details.as_mut() {
+            if let Ok(meta) = details.rx.try_recv() {
+                details.meta = Some(meta);
+            }
+        }
+    }
+
+    /// Opens the contact dialog for `user_id`.
+    pub fn edit_contact(&mut self, user_id: &str) {
+        let contact = self.settings.address_book.get(user_id).cloned().unwrap_or_default();
+        self.contact_editor = Some((user_id.to_string(), contact));
+    }
+
+    /// Stores `contact` in the address book, removing the entry if it is empty.
+    pub fn save_contact(&mut self, user_id: &str, contact: Contact) {
+        self.settings.address_book.set(user_id, contact);
+        if let Err(err) = self.save_settings() {
+            self.show_error(format!("Error saving settings: {}", err));
+        }
+    }
+
+    /// Downloads watched replays that are about to expire. Runs at most once a minute.
+    fn check_watchlist_expiry(&mut self) {
+        if !self.settings.watchlist_auto_download ||
+           self.batch.running ||
+           self.last_watchlist_check.elapsed() < Duration::from_secs(60) {
+            return;
+        }
+        self.last_watchlist_check = Instant::now();
+
+        let due: Vec<String> = self.settings.watchlist.iter()
+            .filter(|entry| entry.is_due(self.settings.watchlist_expiry_window_hours))
+            .filter(|entry| !self.downloaded_replays.contains(&entry.id) && !self.watchlist_attempted.contains(&entry.id))
+            .map(|entry| entry.id.clone())
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+
+        self.watchlist_attempted.extend(due.iter().cloned());
+        self.show_info(format!("Downloading {} watched replays before they expire", due.len()));
+        self.start_batch(due);
+    }
+
+    fn check_batch_download(&mut self) {
+        let finished = self.batch.result.lock().ok().and_then(|mut result| result.take());
+        let Some(result) = finished else {
+            return;
+        };
+
+        self.batch.running = false;
+        match result {
+            Ok(summary) => {
+                self.downloaded_replays.extend(summary.downloaded.iter().map(|(id, _)| id.clone()));
+                if !summary.downloaded.is_empty() {
+                    self.rescan_library();
+                }
+                let message = format!(
+                    "Batch finished: {} downloaded, {} skipped, {} failed",
+                    summary.downloaded.len(), summary.skipped.len(), summary.failed.len()
+                );
+                // Only the summary is shown, the reasons are kept in the history.
+                for (id, error) in &summary.failed {
+                    self.history.push(NotificationType::Error, false, format!("Download of {} failed: {}", id, error));
+                }
+                if summary.failed.is_empty() {
+                    self.show_success(message);
+                } else {
+                    self.show_warning(message);
+                }
+            }
+            Err(e) => self.show_error(format!("Batch download failed: {}", e)),
+        }
+    }
+
+    /// Scans the download directory again once the running scan finished, after downloads,
+    /// deletes or a change of the directory.
+    pub fn rescan_library(&mut self) {
+        self.library_stale = true;
+    }
+
+    pub fn library_scanning(&self) -> bool {
+        self.library_stale || self.library_scan_rx.is_some()
+    }
+
+    fn check_library_scan(&mut self) {
+        if let Some(rx) = &self.library_scan_rx {
+            match rx.try_recv() {
+                Ok(replays) => {
+                    self.downloaded_replays.extend(
+                        replays.iter().filter(|replay| replay.id != UNKNOWN_REPLAY_ID).map(|replay| replay.id.clone()),
+                    );
+                    self.downloaded_files = Arc::new(replays);
+                    self.library_scan_rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => self.library_scan_rx = None,
+            }
+        }
+        if !self.library_stale {
+            return;
+        }
+        self.library_stale = false;
+        let (tx, rx) = mpsc::channel();
+        let download_dir = self.settings.download_dir.clone();
+        let depth = self.settings.naming.folder_depth();
+        thread::spawn(move || {
+            let _ = tx.send(scan_downloaded_replays(&download_dir, depth));
+        });
+        self.library_scan_rx = Some(rx);
     }
 
     fn render_download_progress(&mut self, ctx: &Context) {
@@ -796,30 +1645,21 @@ impl ReplayApp {
     }
 
     pub fn get_filtered_replays(&self) -> Vec<ReplayItem> {
-        self.replay_list.replays.iter()
-            .filter(|replay| {
-                if !self.replay_list.filters.game_mode.is_empty() && 
-                   !replay.game_mode.to_lowercase().contains(&self.replay_list.filters.game_mode.to_lowercase()) {
-                    return false;
-                }
-
-                if !self.replay_list.filters.map_name.is_empty() && 
-                   !replay.map_name.to_lowercase().contains(&self.replay_list.filters.map_name.to_lowercase()) {
-                    return false;
-                }
-
-                if !self.replay_list.filters.workshop_mods.is_empty() && 
-                   !replay.workshop_mods.to_lowercase().contains(&self.replay_list.filters.workshop_mods.to_lowercase()) {
-                    return false;
-                }
-
-                if !self.replay_list.filters.user_id.is_empty() &&
-                   !replay.users.iter().any(|user| user.to_lowercase().contains(&self.replay_list.filters.user_id.to_lowercase())) {
-                    return false;
-                }
+        let replays = if self.search.active { &self.search.results } else { &self.replay_list.replays };
+        let mut filtered: Vec<ReplayItem> = replays.iter()
+            .filter(|replay| self.replay_list.filters.matches(replay, &self.settings.address_book))
+            .cloned()
+            .collect();
+        self.settings.replay_sort.apply(&mut filtered);
+        filtered
+    }
 
-                true
-            })
+    /// Selected replays that are currently loaded, from the page or the search results.
+    pub fn selected_replays(&self) -> Vec<ReplayItem> {
+        let mut seen = HashSet::new();
+        self.replay_list.replays.iter()
+            .chain(self.search.results.iter())
+            .filter(|replay| self.replay_list.selected.contains(&replay.id) && seen.insert(replay.id.clone()))
             .cloned()
             .collect()
     }
@@ -871,9 +1711,50 @@ impl ReplayApp {
                 
                 if let Some(resp) = &response {
                     if resp.clicked() {
-                        self.show_success(format!("Copied user ID: {}", user));
+                        self.show_success(format!("Copied user ID of {}", self.settings.address_book.display_name(user)));
+                    }
+
+                    let label = self.settings.address_book.get(user).map(Contact::label).unwrap_or_default();
+                    if !label.is_empty() {
+                        let strip = egui::Rect::from_min_max(
+                            egui::pos2(resp.rect.left(), resp.rect.bottom() - 16.0),
+                            resp.rect.max,
+                        );
+                        ui.painter().rect_filled(strip, 0.0, egui::Color32::from_black_alpha(180));
+                        ui.painter().with_clip_rect(strip).text(
+                            strip.center(),
+                            egui::Align2::CENTER_CENTER,
+                            &label,
+                            egui::FontId::proportional(11.0),
+                            egui::Color32::WHITE,
+                        );
                     }
                     
+                    let tracked = is_tracked(&self.settings.tracked_players, user);
+                    resp.context_menu(|ui| {
+                        if ui.button(if tracked { "Stop tracking" } else { "Track player" }).clicked() {
+                            self.toggle_tracked_player(user);
+                            ui.close_menu();
+                        }
+                        if ui.button("Edit contact...").clicked() {
+                            self.edit_contact(user);
+                            ui.close_menu();
+                        }
+                        if ui.button("Copy user ID").clicked() {
+                            ctx.copy_text(user.to_string());
+                            ui.close_menu();
+                        }
+                    });
+
+                    if tracked {
+                        ui.painter().rect_stroke(
+                            resp.rect.expand(2.0),
+                            egui::epaint::CornerRadius::ZERO,
+                            egui::Stroke::new(2.0, ui.style().visuals.warn_fg_color),
+                            egui::epaint::StrokeKind::Outside,
+                        );
+                    }
+
                     if resp.hovered() {
                         let rect = resp.rect;
                         ui.painter().rect_stroke(
@@ -883,7 +1764,18 @@ impl ReplayApp {
                             egui::epaint::StrokeKind::Outside,
                         );
                         
-                        resp.clone().on_hover_text(user);
+                        let tracked_nickname = self.settings.tracked_players.iter()
+                            .find(|player| player.user_id.eq_ignore_ascii_case(user) && !player.nickname.is_empty())
+                            .map(|player| player.nickname.as_str());
+                        let mut hover = match (label.as_str(), tracked_nickname) {
+                            ("", None) => user.to_string(),
+                            ("", Some(nickname)) | (nickname, _) => format!("{} ({})", nickname, user),
+                        };
+                        if let Some(notes) = self.settings.address_book.get(user).map(|contact| contact.notes.trim()).filter(|notes| !notes.is_empty()) {
+                            hover.push('\n');
+                            hover.push_str(notes);
+                        }
+                        resp.clone().on_hover_text(hover);
                     }
                 }
             });
@@ -897,7 +1789,7 @@ impl ReplayApp {
         self.show_notification(message.into(), NotificationType::Error)
     }
 
-    fn load_settings() -> Result<Settings, Box<dyn std::error::Error>> {
+    pub fn load_settings() -> Result<Settings, Box<dyn std::error::Error>> {
         let settings_dir = Self::get_settings_dir()?;
         let settings_file = settings_dir.join("settings.json");
         
@@ -906,38 +1798,43 @@ impl ReplayApp {
         }
 
         let settings_str = fs::read_to_string(settings_file)?;
-        let settings = serde_json::from_str(&settings_str)?;
+        let mut settings: Settings = serde_json::from_str(&settings_str)?;
+        settings.migrate();
         Ok(settings)
     }
 
     pub fn save_settings(&self) -> Result<(), Box<dyn std::error::Error>> {
+        Self::write_settings(&self.settings)
+    }
+
+    /// Changes one setting in the saved file only, so edits on the Settings page that were
+    /// not applied yet are not saved along with it.
+    pub fn update_saved_settings(update: impl FnOnce(&mut Settings)) -> Result<(), Box<dyn std::error::Error>> {
+        let mut saved = Self::load_settings()?;
+        update(&mut saved);
+        Self::write_settings(&saved)
+    }
+
+    fn write_settings(settings: &Settings) -> Result<(), Box<dyn std::error::Error>> {
         let settings_dir = Self::get_settings_dir()?;
         fs::create_dir_all(&settings_dir)?;
         
         let settings_file = settings_dir.join("settings.json");
-        let settings_str = serde_json::to_string_pretty(&self.settings)?;
+        let settings_str = serde_json::to_string_pretty(settings)?;
         
         fs::write(settings_file, settings_str)?;
         Ok(())
     }
 
     fn get_settings_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let path = if let Some(proj_dirs) = directories::ProjectDirs::from("com", "PavlovVR", "ReplayToolbox") {
-            proj_dirs.config_dir().to_path_buf()
-        } else {
-            let mut path = std::env::current_dir()?;
-            path.push(".config");
-            path
-        };
-        
-        fs::create_dir_all(&path)?;
-        Ok(path)
+        Ok(settings_dir()?)
     }
 
     fn show_notification(&mut self, message: String, notification_type: NotificationType) {
         let id = self.next_notification_id;
         self.next_notification_id += 1;
-        
+        self.history.push(notification_type, false, message.clone());
+
         self.notifications.push(Notification {
             id,
             message,
@@ -957,6 +1854,23 @@ impl ReplayApp {
         self.show_notification(message.into(), NotificationType::Warning)
     }
     
+    /// Records changes of the shared status line, which background threads overwrite.
+    fn check_status_history(&mut self) {
+        let status = match self.status.lock() {
+            Ok(status) if *status != self.history.last_status => status.clone(),
+            _ => return,
+        };
+        let severity = if status.contains("Error") || status.contains("Failed") {
+            NotificationType::Error
+        } else {
+            NotificationType::Info
+        };
+        self.history.last_status = status.clone();
+        if !status.is_empty() {
+            self.history.push(severity, true, status);
+        }
+    }
+
     fn update_notifications(&mut self) {
         let now = Instant::now();
         
@@ -964,7 +1878,7 @@ impl ReplayApp {
             now.duration_since(notification.created_at).as_millis() < notification.duration_ms as u128
         });
         
-        self.notifications.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        self.notifications.sort_by_key(|n| n.created_at);
         
         for notification in &mut self.notifications {
             let elapsed_ms = now.duration_since(notification.created_at).as_millis() as f32;
@@ -1066,23 +1980,6 @@ impl ReplayApp {
         let api_token = self.settings.modio_api_token.clone();
         
         thread::spawn(move || {
-            let client = match reqwest::blocking::Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build() {
-                    Ok(client) => client,
-                    Err(_) => {
-                        let _ = mod_info_tx.send(ModInfo {
-                            id: mod_id,
-                            name: "Error".to_string(),
-                            description: "Failed to create HTTP client".to_string(),
-                            thumbnail_url: None,
-                            is_loading: false,
-                            failed: true,
-                        });
-                        return;
-                    }
-                };
-            
             // If no API token is provided, show basic info
             if api_token.is_empty() {
                 let _ = mod_info_tx.send(ModInfo {
@@ -1099,7 +1996,7 @@ impl ReplayApp {
             // Pavlov's game ID on mod.io is 3959
             let url = format!("{}/games/3959/mods/{}?api_key={}", api_url, mod_id, api_token);
             
-            match client.get(&url).send() {
+            match http_client::client().get(&url).timeout(INTERACTIVE_TIMEOUT).send() {
                 Ok(response) => {
                     let status = response.status();
                     if status.is_success() {
@@ -1165,20 +2062,7 @@ impl ReplayApp {
     }
 
     pub fn parse_mod_ids(&self, workshop_mods: &str) -> Vec<String> {
-        let mut mod_ids = Vec::new();
-        
-        let cleaned_str = workshop_mods.strip_prefix("AdditionalMods=").unwrap_or(workshop_mods);
-        
-        for part in cleaned_str.split('-') {
-            if part.starts_with("UGC") {
-                if let Some(slash_pos) = part.find('/') {
-                    let id = &part[3..slash_pos];
-                    mod_ids.push(id.to_string());
-                }
-            }
-        }
-        
-        mod_ids
+        parse_mod_ids(workshop_mods)
     }
 
     pub fn load_mod_thumbnail(&mut self, mod_id: String, thumbnail_url: String) {
@@ -1191,33 +2075,18 @@ impl ReplayApp {
         let thumbnail_tx = self.mod_thumbnail_tx.clone();
         
         thread::spawn(move || {
-            let client = match reqwest::blocking::Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build() {
-                    Ok(client) => client,
-                    Err(_) => return,
-                };
-            
-            match client.get(&thumbnail_url).send() {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        match response.bytes() {
-                            Ok(bytes) => {
-                                match image::load_from_memory(&bytes) {
-                                    Ok(img) => {
-                                        let img = img.to_rgba8();
-                                        let size = [img.width() as usize, img.height() as usize];
-                                        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &img.into_raw());
-                                        let _ = thumbnail_tx.send((mod_id, color_image));
-                                    },
-                                    Err(_) => {} // Failed to parse image
-                                }
-                            },
-                            Err(_) => {} // Failed to get bytes
+            // Failures are silent; the popup keeps showing the loading spinner.
+            if let Ok(response) = http_client::client().get(&thumbnail_url).timeout(INTERACTIVE_TIMEOUT).send() {
+                if response.status().is_success() {
+                    if let Ok(bytes) = response.bytes() {
+                        if let Ok(img) = image::load_from_memory(&bytes) {
+                            let img = img.to_rgba8();
+                            let size = [img.width() as usize, img.height() as usize];
+                            let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &img.into_raw());
+                            let _ = thumbnail_tx.send((mod_id, color_image));
                         }
                     }
-                },
-                Err(_) => {} // Failed to send request
+                }
             }
         });
     }
@@ -1303,13 +2172,33 @@ impl App for ReplayApp {
         }
         
         // Update notifications
+        self.check_status_history();
         self.update_notifications();
         
         self.render_download_progress(ctx);
+        self.check_batch_download();
+        self.check_library_scan();
+        self.check_watchlist_expiry();
+        self.check_auto_downloads();
+        self.check_auto_download_rules();
+        self.check_download_script();
+        pages::handle_shortcuts(self, ctx);
+        pages::render_batch_dialog(self, ctx);
+        pages::render_watchlist_dialog(self, ctx);
+        pages::render_contact_dialog(self, ctx);
+        self.check_replay_details();
+        pages::render_details_dialog(self, ctx);
+        pages::render_history_dialog(self, ctx);
+        pages::render_log_dialog(self, ctx);
+        pages::render_shortcuts_dialog(self, ctx);
+        self.check_catalogue_search();
+        self.check_listing();
+        self.check_live_matches();
+        self.check_tracking_errors();
         
         while let Ok((user, color_image)) = self.profile_rx.try_recv() {
             let texture_handle = ctx.load_texture(
-                &format!("avatar_{}", user),
+                format!("avatar_{}", user),
                 color_image,
                 egui::TextureOptions {
                     magnification: egui::TextureFilter::Linear,
@@ -1327,7 +2216,7 @@ impl App for ReplayApp {
         
         while let Ok((mod_id, color_image)) = self.mod_thumbnail_rx.try_recv() {
             let texture_handle = ctx.load_texture(
-                &format!("mod_thumbnail_{}", mod_id),
+                format!("mod_thumbnail_{}", mod_id),
                 color_image,
                 egui::TextureOptions {
                     magnification: egui::TextureFilter::Linear,
@@ -1341,6 +2230,7 @@ impl App for ReplayApp {
         
         while let Ok(replay_id) = self.downloaded_rx.try_recv() {
             self.downloaded_replays.insert(replay_id.clone());
+            self.rescan_library();
             self.show_success(format!("Replay {} downloaded successfully", replay_id));
         }
 
@@ -1374,6 +2264,26 @@ impl App for ReplayApp {
                     self.current_page = Page::Main;
                 });
 
+                ui.add_sized(
+                    [80.0, button_height],
+                    egui::SelectableLabel::new(
+                        self.current_page == Page::Live,
+                        "Live"
+                    )
+                ).clicked().then(|| {
+                    self.current_page = Page::Live;
+                });
+
+                ui.add_sized(
+                    [80.0, button_height],
+                    egui::SelectableLabel::new(
+                        self.current_page == Page::Players,
+                        "Players"
+                    )
+                ).clicked().then(|| {
+                    self.open_page(Page::Players);
+                });
+
                 ui.add_sized(
                     [120.0, button_height],
                     egui::SelectableLabel::new(
@@ -1404,6 +2314,24 @@ impl App for ReplayApp {
                     ).clicked().then(|| {
                         self.current_page = Page::Settings;
                     });
+
+                    let history_label = match self.history.unread_errors {
+                        0 => "History".to_string(),
+                        errors => format!("History ({})", errors),
+                    };
+                    ui.add_sized(
+                        [90.0, button_height],
+                        egui::SelectableLabel::new(self.history.open, history_label)
+                    ).clicked().then(|| {
+                        self.history.open = !self.history.open;
+                    });
+
+                    ui.add_sized(
+                        [32.0, button_height],
+                        egui::SelectableLabel::new(self.shortcuts_open, "?")
+                    ).on_hover_text("Keyboard shortcuts (F1)").clicked().then(|| {
+                        self.shortcuts_open = !self.shortcuts_open;
+                    });
                 });
             });
             ui.add_space(4.0);
@@ -1413,6 +2341,8 @@ impl App for ReplayApp {
         CentralPanel::default().show(ctx, |ui| {
             match self.current_page {
                 Page::Main => pages::render_main_page(self, ui, ctx),
+                Page::Live => pages::render_live_page(self, ui, ctx),
+                Page::Players => pages::render_players_page(self, ui, ctx),
                 Page::ProcessLocal => pages::render_process_page(self, ui),
                 Page::Settings => pages::render_settings_page(self, ui),
                 Page::Manage => pages::render_manage_page(self, ui, ctx),
@@ -1432,14 +2362,16 @@ impl App for ReplayApp {
             self.is_downloading = false;
         }
 
+        // Active auto-download rules keep the listing refreshing on the other pages too.
         if self.settings.auto_refresh_enabled && 
            self.last_refresh_time.elapsed() > Duration::from_secs(self.settings.auto_refresh_interval_mins * 60) &&
-           self.current_page == Page::Main && 
+           (self.current_page == Page::Main || self.settings.has_active_rules()) && 
+           !self.replay_list.is_loading &&
            !self.is_downloading {
             self.refresh_replays();
         } else if self.settings.auto_download_enabled &&
-                 !self.is_downloading && 
-                 self.current_page == Page::Main {
+                 self.auto_download_pending &&
+                 !self.is_downloading {
             self.check_auto_download_triggers();
         }
 
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use crate::cli::{
+    configure_network, exit_code, CliArg, CliCommand, CliError, CliResult, Console, ParsedArgs, CLI_ARG_CA_CERT,
+    CLI_ARG_HELP, CLI_ARG_INSECURE, CLI_ARG_NO_PROXY, CLI_ARG_PROXY, EXIT_FAILURE, EXIT_SUCCESS,
+};
+use crate::tools::batch::{parse_replay_ids, run_batch, BatchEvent, BatchOptions, DEFAULT_JOBS};
+use crate::app::ReplayApp;
+use crate::tools::naming::NamingOptions;
+use crate::tools::error::{ErrorKind, ReplayError};
+use crate::tools::library::{record_download, write_replay_file};
+use crate::tools::build_replay::{build_replay, write_replay};
+use crate::tools::replay_processor::{download_replay_parts, find_replay};
+
+pub const CLI_ARG_REPLAY: CliArg = CliArg {
+    key: "-r",
+    flag: false,
+    description: "Replay ID. Same as giving the ID as an argument.",
+};
+pub const CLI_ARG_OUTPUT: CliArg = CliArg {
+    key: "-o",
+    flag: false,
+    description: "Output file name, relative to the output directory. '-' writes the replay to stdout. Single replay only.",
+};
+pub const CLI_ARG_FROM_FILE: CliArg = CliArg {
+    key: "--from-file",
+    flag: false,
+    description: "Read replay IDs from a file, one per line. '-' reads standard input.",
+};
+pub const CLI_ARG_JOBS: CliArg = CliArg {
+    key: "--jobs",
+    flag: false,
+    description: "Number of replays downloaded at the same time (default 3).",
+};
+pub const CLI_ARG_FORCE: CliArg = CliArg {
+    key: "--force",
+    flag: true,
+    description: "Download again even if the replay already exists in the output directory.",
+};
+pub const CLI_ARG_JSON: CliArg = CliArg {
+    key: "--json",
+    flag: true,
+    description: "Print progress, metadata, output paths and the final status as newline-delimited JSON.",
+};
+pub const CLI_ARG_DIR: CliArg = CliArg {
+    key: "--dir",
+    flag: false,
+    description: "Output directory. Defaults to the current directory.",
+};
+pub const CLI_ARG_NAME_TEMPLATE: CliArg = CliArg {
+    key: "--name-template",
+    flag: false,
+    description: "File name template, e.g. '{mode}/{map}/{date:%Y-%m-%d} {id}'. Must contain {id}. Defaults to the template in settings.",
+};
+pub const CLI_ARG_ALTERNATE_NAME: CliArg = CliArg {
+    key: "--alt",
+    flag: true,
+    description: "Preset: alternate naming schema puts timestamp first. (file browsers can easily sort timeline by name).",
+};
+pub const CLI_ARG_ISO8601: CliArg = CliArg {
+    key: "--iso8601",
+    flag: true,
+    description: "Preset: sets timestamp in ISO8601 format. ':' is written as '-' so names are valid on Windows.",
+};
+pub const CLI_ARG_UTC: CliArg = CliArg {
+    key: "--utc",
+    flag: true,
+    description: "Timestamp is in UTC timezone.",
+};
+
+pub const COMMAND: CliCommand = CliCommand {
+    name: "download",
+    usage: "download <REPLAY_ID>... [-] [--from-file FILE] [-o FILE] [--dir DIR] [--jobs N] [--force] [--name-template T | --alt | --iso8601] [--utc] [--json]",
+    description: "Download replays from PavlovTV and build the .replay files. '-' reads IDs from standard input.",
+    args: &[
+        CLI_ARG_REPLAY, CLI_ARG_OUTPUT, CLI_ARG_DIR, CLI_ARG_FROM_FILE, CLI_ARG_JOBS, CLI_ARG_FORCE,
+        CLI_ARG_NAME_TEMPLATE, CLI_ARG_ALTERNATE_NAME, CLI_ARG_ISO8601, CLI_ARG_UTC, CLI_ARG_JSON,
+        CLI_ARG_PROXY, CLI_ARG_NO_PROXY, CLI_ARG_CA_CERT, CLI_ARG_INSECURE, CLI_ARG_HELP,
+    ],
+    run,
+};
+
+/// Naming from settings, overridden by `--name-template` or the `--alt`/`--iso8601` presets.
+pub fn naming_options(args: &ParsedArgs) -> Result<NamingOptions, CliError> {
+    let alternate = args.flag(CLI_ARG_ALTERNATE_NAME.key);
+    let iso8601 = args.flag(CLI_ARG_ISO8601.key);
+    let utc = args.flag(CLI_ARG_UTC.key);
+
+    let mut options = if alternate || iso8601 {
+        NamingOptions::preset(alternate, iso8601, utc)
+    } else {
+        ReplayApp::load_settings().unwrap_or_default().naming
+    };
+    if let Some(template) = args.value(CLI_ARG_NAME_TEMPLATE.key) {
+        if alternate || iso8601 {
+            return Err(CliError::usage("--name-template cannot be combined with --alt or --iso8601"));
+        }
+        options.template = template.to_string();
+    }
+    options.utc |= utc;
+
+    options.validate().map_err(|e| CliError::usage(e.to_string()))?;
+    Ok(options)
+}
+
+pub fn output_dir(args: &ParsedArgs) -> Result<PathBuf, CliError> {
+    match args.value(CLI_ARG_DIR.key) {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => std::env::current_dir()
+            .map_err(|e| CliError::failure(format!("Failed to read working directory: {}", e))),
+    }
+}
+
+fn read_stdin() -> Result<String, CliError> {
+    let mut text = String::new();
+    std::io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|e| CliError::failure(format!("Failed to read standard input: {}", e)))?;
+    Ok(text)
+}
+
+/// Collects replay IDs from `-r`, positional arguments, `--from-file` and standard input.
+fn collect_replay_ids(args: &ParsedArgs) -> Result<Vec<String>, CliError> {
+    let mut text = args.values(CLI_ARG_REPLAY.key).join("\n");
+    let mut read_stdin_ids = false;
+    for positional in &args.positionals {
+        if positional == "-" {
+            read_stdin_ids = true;
+        } else {
+            text.push('\n');
+            text.push_str(positional);
+        }
+    }
+
+    if let Some(file) = args.value(CLI_ARG_FROM_FILE.key) {
+        if file == "-" {
+            read_stdin_ids = true;
+        } else {
+            let contents = fs::read_to_string(file)
+                .map_err(|e| CliError::failure(format!("Failed to read {}: {}", file, e)))?;
+            text.push('\n');
+            text.push_str(&contents);
+        }
+    }
+
+    if read_stdin_ids {
+        text.push('\n');
+        text.push_str(&read_stdin()?);
+    }
+
+    Ok(parse_replay_ids(&text))
+}
+
+fn json_event(event: &BatchEvent) -> serde_json::Value {
+    match event {
+        BatchEvent::Skipped { id, reason } => json!({ "event": "skipped", "id": id, "reason": reason }),
+        BatchEvent::Started { id } => json!({ "event": "started", "id": id }),
+        BatchEvent::Progress { id, current, total } => {
+            json!({ "event": "progress", "id": id, "current": current, "total": total })
+        }
+        BatchEvent::Metadata { id, meta, info } => {
+            json!({ "event": "metadata", "id": id, "meta": meta, "replay": info })
+        }
+        BatchEvent::Finished { id, path, bytes } => {
+            json!({ "event": "saved", "id": id, "path": path, "bytes": bytes })
+        }
+        BatchEvent::Failed { id, error } => json!({
+            "event": "failed",
+            "id": id,
+            "kind": error.kind,
+            "exit_code": exit_code(error.kind),
+            "message": error.message,
+        }),
+    }
+}
+
+/// Prints batch events as text, or as one JSON object per line with `--json`.
+fn report(event: BatchEvent, console: Console) {
+    if console.json {
+        console.print(json_event(&event));
+        return;
+    }
+    match event {
+        BatchEvent::Skipped { id, reason } => console.print(format!("Skipped {}: {}", id, reason)),
+        BatchEvent::Started { id } => console.print(format!("Downloading {}...", id)),
+        BatchEvent::Progress { .. } | BatchEvent::Metadata { .. } => {}
+        BatchEvent::Finished { id, path, .. } => console.print(format!("Saved {} to '{}'", id, path.display())),
+        BatchEvent::Failed { id, error } => console.print(format!("Failed {}: {}", id, error)),
+    }
+}
+
+/// Downloads one replay and streams it to `output_file`, or to stdout when it is `None`.
+fn download_single(
+    replay_id: &str,
+    output_file: Option<&Path>,
+    on_event: &(dyn Fn(BatchEvent) + Send + Sync),
+) -> Result<usize, ReplayError> {
+    on_event(BatchEvent::Started { id: replay_id.to_string() });
+    let progress_id = replay_id.to_string();
+    let progress = Box::new(move |current: usize, total: usize| {
+        on_event(BatchEvent::Progress { id: progress_id.clone(), current, total });
+    });
+
+    let replay = find_replay(replay_id)
+        .and_then(|info| download_replay_parts(info, Some(progress)))
+        .map_err(|e| ReplayError::from_boxed(e).context("Failed to download replay data"))?;
+    on_event(BatchEvent::Metadata {
+        id: replay_id.to_string(),
+        meta: Box::new(replay.meta.clone()),
+        info: Box::new(replay.info.clone()),
+    });
+
+    let written = match output_file {
+        Some(path) => {
+            let data = build_replay(&replay.parts).map_err(|e| ReplayError::new(ErrorKind::Parse, e.to_string()))?;
+            write_replay_file(path, &data).map(|_| {
+                // Without an index entry the ID is guessed from the file name, so a failure does not fail the download.
+                let _ = record_download(&replay.info, path);
+                data.len()
+            })
+        }
+        None => write_replay(&replay.parts, &mut io::stdout().lock()),
+    };
+    written.map_err(|e| ReplayError::new(ErrorKind::Write, format!("Failed to save replay file: {}", e)))
+}
+
+fn print_done(console: Console, downloaded: usize, skipped: usize, failed: usize, code: i32) {
+    if console.json {
+        console.print(json!({
+            "event": "done",
+            "status": if failed == 0 { "ok" } else { "failed" },
+            "downloaded": downloaded,
+            "skipped": skipped,
+            "failed": failed,
+            "exit_code": code,
+        }));
+    } else {
+        console.print(format!("Done: {} downloaded, {} skipped, {} failed.", downloaded, skipped, failed));
+    }
+}
+
+fn run(args: &ParsedArgs) -> CliResult {
+    let ids = collect_replay_ids(args)?;
+    if ids.is_empty() {
+        return Err(CliError::usage("Missing replay ID. See 'download --help'."));
+    }
+    let jobs = args.parsed_value::<usize>(CLI_ARG_JOBS.key)?.unwrap_or(DEFAULT_JOBS);
+    if jobs == 0 {
+        return Err(CliError::usage("--jobs must be at least 1"));
+    }
+    let console = Console {
+        json: args.flag(CLI_ARG_JSON.key),
+        stderr: args.value(CLI_ARG_OUTPUT.key) == Some("-"),
+    };
+    let on_event = |event: BatchEvent| report(event, console);
+
+    configure_network(args)?;
+
+    if let Some(output) = args.value(CLI_ARG_OUTPUT.key) {
+        let [replay_id] = ids.as_slice() else {
+            return Err(CliError::usage("-o can only be used with a single replay ID"));
+        };
+        let output_file = match output {
+            "-" => None,
+            output if Path::new(output).is_absolute() => Some(PathBuf::from(output)),
+            output => Some(output_dir(args)?.join(output)),
+        };
+
+        return match download_single(replay_id, output_file.as_deref(), &on_event) {
+            Ok(bytes) => {
+                let path = output_file.unwrap_or_else(|| PathBuf::from("-"));
+                on_event(BatchEvent::Finished { id: replay_id.clone(), path, bytes });
+                print_done(console, 1, 0, 0, EXIT_SUCCESS);
+                Ok(())
+            }
+            Err(error) => {
+                on_event(BatchEvent::Failed { id: replay_id.clone(), error: error.clone() });
+                print_done(console, 0, 0, 1, exit_code(error.kind));
+                Err(error.into())
+            }
+        };
+    }
+
+    let options = BatchOptions {
+        download_dir: output_dir(args)?,
+        naming: naming_options(args)?,
+        jobs,
+        skip_existing: !args.flag(CLI_ARG_FORCE.key),
+        file_names: HashMap::new(),
+    };
+
+    if !console.json {
+        console.print(format!("Downloading {} replay(s) to '{}'...", ids.len(), options.download_dir.display()));
+    }
+    let summary = match run_batch(&ids, &options, &on_event) {
+        Ok(summary) => summary,
+        Err(e) => {
+            let error = CliError::from(e);
+            print_done(console, 0, 0, ids.len(), error.code);
+            return Err(error);
+        }
+    };
+
+    let code = match summary.failure_kind() {
+        _ if summary.failed.is_empty() => EXIT_SUCCESS,
+        Some(kind) => exit_code(kind),
+        None => EXIT_FAILURE,
+    };
+    print_done(console, summary.downloaded.len(), summary.skipped.len(), summary.failed.len(), code);
+    if !console.json {
+        for (id, error) in &summary.failed {
+            console.print(format!("  {} - {}", id, error));
+        }
+    }
+
+    if code != EXIT_SUCCESS {
+        return Err(CliError {
+            code,
+            message: format!("{} of {} replays failed", summary.failed.len(), ids.len()),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::Path;
+
+use crate::cli::{
+    configure_network, CliCommand, CliError, CliResult, ParsedArgs, CLI_ARG_CA_CERT, CLI_ARG_HELP,
+    CLI_ARG_INSECURE, CLI_ARG_NO_PROXY, CLI_ARG_PROXY,
+};
+use crate::tools::replay_processor::fetch_metadata;
+use crate::tools::replay_reader::read_replay_summary;
+
+pub const COMMAND: CliCommand = CliCommand {
+    name: "info",
+    usage: "info <REPLAY_ID | FILE.replay>",
+    description: "Show the metadata of a replay on PavlovTV or of a local .replay file.",
+    args: &[CLI_ARG_PROXY, CLI_ARG_NO_PROXY, CLI_ARG_CA_CERT, CLI_ARG_INSECURE, CLI_ARG_HELP],
+    run,
+};
+
+fn run(args: &ParsedArgs) -> CliResult {
+    let target = match args.positionals.as_slice() {
+        [target] => target,
+        [] => return Err(CliError::usage("Missing replay ID or file. See 'info --help'.")),
+        _ => return Err(CliError::usage("Expected exactly one replay ID or file")),
+    };
+
+    let path = Path::new(target);
+    if path.is_file() {
+        let data = fs::read(path)
+            .map_err(|e| CliError::failure(format!("Failed to read {}: {}", path.display(), e)))?;
+        let summary = read_replay_summary(&data)?;
+        println!("File:        {}", path.display());
+        println!("Map:         {}", summary.map_name);
+        println!("Game mode:   {}", summary.game_mode);
+        println!("Competitive: {}", summary.competitive);
+        println!("Live:        {}", summary.live);
+        println!("Total time:  {} ms", summary.total_time);
+        println!("Version:     {}", summary.version);
+        println!("Created:     {}", summary.created.map(|dt| dt.to_rfc3339()).unwrap_or_default());
+        println!("Mods:        {}", summary.workshop_mods);
+        return Ok(());
+    }
+
+    configure_network(args)?;
+    let meta = fetch_metadata(target)?;
+    println!("Replay ID:   {}", target);
+    println!("Map:         {}", meta.friendly_name);
+    println!("Game mode:   {}", meta.game_mode);
+    println!("Competitive: {}", meta.competitive);
+    println!("Live:        {}", meta.live);
+    println!("Total time:  {} ms", meta.total_time);
+    println!("Version:     {}", meta.version);
+    println!("Created:     {}", meta.created);
+    println!("Mods:        {}", meta.workshop_mods);
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use crate::cli::{
+    configure_network, CliArg, CliCommand, CliError, CliResult, ParsedArgs, CLI_ARG_CA_CERT,
+    CLI_ARG_HELP, CLI_ARG_INSECURE, CLI_ARG_NO_PROXY, CLI_ARG_PROXY,
+};
+use crate::app::ReplayApp;
+use crate::tools::address_book::AddressBook;
+use crate::tools::listing::{
+    fetch_page, search_all_pages, total_pages, PlatformFilter, ReplayFilters, PAGE_SIZE,
+};
+use crate::tools::replay_processor::{ApiReplay, ReplayItem};
+
+pub const CLI_ARG_PAGE: CliArg = CliArg {
+    key: "--page",
+    flag: false,
+    description: "Page to list, starting at 1.",
+};
+pub const CLI_ARG_ALL: CliArg = CliArg {
+    key: "--all",
+    flag: true,
+    description: "Fetch every page instead of a single one.",
+};
+pub const CLI_ARG_LIVE: CliArg = CliArg {
+    key: "--live",
+    flag: true,
+    description: "List matches that are still in progress.",
+};
+pub const CLI_ARG_MODE: CliArg = CliArg {
+    key: "--mode",
+    flag: false,
+    description: "Only this game mode (e.g. SND).",
+};
+pub const CLI_ARG_MAP: CliArg = CliArg {
+    key: "--map",
+    flag: false,
+    description: "Only maps containing this text.",
+};
+pub const CLI_ARG_MODS: CliArg = CliArg {
+    key: "--mods",
+    flag: false,
+    description: "Only replays whose workshop mods contain this text.",
+};
+pub const CLI_ARG_USER: CliArg = CliArg {
+    key: "--user",
+    flag: false,
+    description: "Only replays with a user ID, or an address book nickname, team tag or note, containing this text.",
+};
+pub const CLI_ARG_PLATFORM: CliArg = CliArg {
+    key: "--platform",
+    flag: false,
+    description: "all, quest or pc.",
+};
+pub const CLI_ARG_COMPETITIVE: CliArg = CliArg {
+    key: "--competitive",
+    flag: true,
+    description: "Only competitive matches.",
+};
+pub const CLI_ARG_CASUAL: CliArg = CliArg {
+    key: "--casual",
+    flag: true,
+    description: "Only casual matches.",
+};
+pub const CLI_ARG_JSON: CliArg = CliArg {
+    key: "--json",
+    flag: true,
+    description: "Print a JSON array of replays instead of a table.",
+};
+
+pub const COMMAND: CliCommand = CliCommand {
+    name: "list",
+    usage: "list [--page N | --all] [--live] [--mode M] [--map M] [--mods M] [--user ID] [--platform P] [--competitive | --casual] [--json]",
+    description: "List replays available on PavlovTV.",
+    args: &[
+        CLI_ARG_PAGE, CLI_ARG_ALL, CLI_ARG_LIVE, CLI_ARG_MODE, CLI_ARG_MAP, CLI_ARG_MODS, CLI_ARG_USER,
+        CLI_ARG_PLATFORM, CLI_ARG_COMPETITIVE, CLI_ARG_CASUAL, CLI_ARG_JSON,
+        CLI_ARG_PROXY, CLI_ARG_NO_PROXY, CLI_ARG_CA_CERT, CLI_ARG_INSECURE, CLI_ARG_HELP,
+    ],
+    run,
+};
+
+pub fn print_table(replays: &[ApiReplay]) {
+    println!("{:26} {:24} {:10} {:25} {:>7}", "ID", "MAP", "MODE", "CREATED", "PLAYERS");
+    for replay in replays {
+        println!(
+            "{:26} {:24} {:10} {:25} {:>7}",
+            replay.id,
+            replay.map_name,
+            replay.game_mode,
+            replay.created,
+            replay.users.as_ref().map_or(0, Vec::len),
+        );
+    }
+}
+
+pub fn parse_platform(value: Option<&str>) -> Result<PlatformFilter, CliError> {
+    match value.map(str::to_lowercase).as_deref() {
+        None | Some("all") => Ok(PlatformFilter::All),
+        Some("quest") | Some("shack") => Ok(PlatformFilter::Quest),
+        Some("pc") => Ok(PlatformFilter::PC),
+        Some(other) => Err(CliError::usage(format!("Invalid platform '{}', expected all, quest or pc", other))),
+    }
+}
+
+/// Builds the listing filters shared by `list` and other commands that select replays.
+pub fn filters_from_args(args: &ParsedArgs) -> Result<ReplayFilters, CliError> {
+    let competitive = match (args.flag(CLI_ARG_COMPETITIVE.key), args.flag(CLI_ARG_CASUAL.key)) {
+        (true, true) => return Err(CliError::usage("--competitive and --casual are mutually exclusive")),
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        (false, false) => None,
+    };
+
+    Ok(ReplayFilters {
+        game_mode: args.value(CLI_ARG_MODE.key).unwrap_or_default().to_string(),
+        map_name: args.value(CLI_ARG_MAP.key).unwrap_or_default().to_string(),
+        workshop_mods: args.value(CLI_ARG_MODS.key).unwrap_or_default().to_string(),
+        platform: parse_platform(args.value(CLI_ARG_PLATFORM.key))?,
+        user_id: args.value(CLI_ARG_USER.key).unwrap_or_default().to_string(),
+        competitive,
+    })
+}
+
+fn matching(replays: Vec<ApiReplay>, filters: &ReplayFilters, book: &AddressBook) -> Vec<ApiReplay> {
+    replays
+        .into_iter()
+        .filter(|replay| filters.matches(&ReplayItem::from(replay.clone()), book))
+        .collect()
+}
+
+fn run(args: &ParsedArgs) -> CliResult {
+    let page = args.parsed_value::<usize>(CLI_ARG_PAGE.key)?;
+    let all_pages = args.flag(CLI_ARG_ALL.key);
+    if page.is_some() && all_pages {
+        return Err(CliError::usage("--page and --all are mutually exclusive"));
+    }
+    let page = page.unwrap_or(1).max(1);
+    let filters = filters_from_args(args)?;
+    configure_network(args)?;
+    // Nicknames from the GUI's address book are searchable with --user.
+    let book = ReplayApp::load_settings().map(|settings| settings.address_book).unwrap_or_default();
+
+    let live = args.flag(CLI_ARG_LIVE.key);
+
+    let (replays, footer) = if all_pages {
+        let mut replays = Vec::new();
+        let mut total = 0;
+        search_all_pages(&filters, &book, live, |matches, _, page_total| {
+            total = page_total;
+            replays.extend(matches);
+            true
+        })?;
+        let footer = format!("{} of {} replays matched", replays.len(), total);
+        (replays, footer)
+    } else {
+        let response = fetch_page(&filters.query((page - 1) * PAGE_SIZE, live))?;
+        let footer = format!(
+            "Page {} of {} ({} replays total)",
+            page, total_pages(response.total).max(1), response.total
+        );
+        (matching(response.replays, &filters, &book), footer)
+    };
+
+    if args.flag(CLI_ARG_JSON.key) {
+        let json = serde_json::to_string_pretty(&replays)
+            .map_err(|e| CliError::failure(format!("Failed to serialize replays: {}", e)))?;
+        println!("{}", json);
+    } else {
+        print_table(&replays);
+        println!("{}", footer);
+    }
+    Ok(())
+}
@@ -0,0 +1,454 @@
+mod download;
+mod info;
+mod list;
+mod process;
+mod verify;
+mod watch;
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::app::ReplayApp;
+use crate::tools::error::{ErrorKind, ReplayError};
+use crate::tools::http_client;
+use crate::tools::logging::LogFilter;
+
+pub const EXIT_SUCCESS: i32 = 0;
+/// The command failed for a reason not covered by a more specific code below.
+pub const EXIT_FAILURE: i32 = 1;
+/// Invalid command line: unknown command or flag, missing value.
+pub const EXIT_USAGE: i32 = 2;
+/// The replay does not exist or has expired.
+pub const EXIT_NOT_FOUND: i32 = 3;
+/// The match is still being recorded.
+pub const EXIT_NOT_RECORDED: i32 = 4;
+/// The server could not be reached or returned an error.
+pub const EXIT_NETWORK: i32 = 5;
+/// A server response or replay file could not be parsed.
+pub const EXIT_PARSE: i32 = 6;
+/// The output could not be written.
+pub const EXIT_WRITE: i32 = 7;
+
+pub fn exit_code(kind: ErrorKind) -> i32 {
+    match kind {
+        ErrorKind::NotFound => EXIT_NOT_FOUND,
+        ErrorKind::NotRecorded => EXIT_NOT_RECORDED,
+        ErrorKind::Network => EXIT_NETWORK,
+        ErrorKind::Parse => EXIT_PARSE,
+        ErrorKind::Write => EXIT_WRITE,
+        ErrorKind::Other => EXIT_FAILURE,
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct CliArg {
+    pub key: &'static str,
+    pub flag: bool,
+    pub description: &'static str,
+}
+
+pub struct CliCommand {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub args: &'static [CliArg],
+    pub run: fn(&ParsedArgs) -> CliResult,
+}
+
+pub const CLI_ARG_HELP: CliArg = CliArg {
+    key: "--help",
+    flag: true,
+    description: "Print help for this command.",
+};
+pub const CLI_ARG_PROXY: CliArg = CliArg {
+    key: "--proxy",
+    flag: false,
+    description: "HTTP(S) proxy URL for all requests. Overrides the proxy in settings.",
+};
+pub const CLI_ARG_NO_PROXY: CliArg = CliArg {
+    key: "--no-proxy",
+    flag: false,
+    description: "Comma separated hosts that bypass the proxy.",
+};
+pub const CLI_ARG_CA_CERT: CliArg = CliArg {
+    key: "--ca-cert",
+    flag: false,
+    description: "Extra trusted root certificate (PEM or DER). Can be given multiple times.",
+};
+pub const CLI_ARG_INSECURE: CliArg = CliArg {
+    key: "--insecure",
+    flag: true,
+    description: "Accept invalid/self-signed TLS certificates from the replay server (local mirrors only).",
+};
+/// Accepted by every command and by the graphical interface, see `take_log_level`.
+pub const CLI_ARG_LOG_LEVEL: CliArg = CliArg {
+    key: "--log-level",
+    flag: false,
+    description: "Log filter like 'debug' or 'warn,tools::batch=debug'. Also logs to stderr.",
+};
+
+pub const COMMANDS: [&CliCommand; 6] = [
+    &list::COMMAND,
+    &download::COMMAND,
+    &info::COMMAND,
+    &process::COMMAND,
+    &verify::COMMAND,
+    &watch::COMMAND,
+];
+
+#[derive(Debug)]
+pub struct CliError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl CliError {
+    pub fn usage(message: impl Into<String>) -> Self {
+        Self { code: EXIT_USAGE, message: message.into() }
+    }
+
+    pub fn failure(message: impl Into<String>) -> Self {
+        Self { code: EXIT_FAILURE, message: message.into() }
+    }
+
+    pub fn from_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { code: exit_code(kind), message: message.into() }
+    }
+}
+
+impl From<ReplayError> for CliError {
+    fn from(err: ReplayError) -> Self {
+        Self::from_kind(err.kind, err.message)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for CliError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self::from_kind(ReplayError::classify(err.as_ref()), err.to_string())
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for CliError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        Self::from_kind(ReplayError::classify(err.as_ref()), err.to_string())
+    }
+}
+
+pub type CliResult = Result<(), CliError>;
+
+/// Where a command prints its messages. When the replay itself is written to stdout
+/// (`-o -`), every message goes to stderr so the output stays a valid replay file.
+#[derive(Clone, Copy)]
+pub struct Console {
+    pub json: bool,
+    pub stderr: bool,
+}
+
+impl Console {
+    pub fn print(&self, message: impl fmt::Display) {
+        if self.stderr {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+}
+
+/// Arguments of one command after validation against its `CliArg` table.
+#[derive(Default)]
+pub struct ParsedArgs {
+    pub positionals: Vec<String>,
+    values: Vec<(&'static str, String)>,
+    flags: Vec<&'static str>,
+}
+
+impl ParsedArgs {
+    pub fn flag(&self, key: &str) -> bool {
+        self.flags.contains(&key)
+    }
+
+    /// Last value given for `key`.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.values.iter().rev().find(|(k, _)| *k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// All values given for a repeatable `key`, in order.
+    pub fn values(&self, key: &str) -> Vec<&str> {
+        self.values.iter().filter(|(k, _)| *k == key).map(|(_, v)| v.as_str()).collect()
+    }
+
+    pub fn parsed_value<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, CliError> {
+        match self.value(key) {
+            Some(raw) => raw
+                .parse::<T>()
+                .map(Some)
+                .map_err(|_| CliError::usage(format!("Invalid value '{}' for {}", raw, key))),
+            None => Ok(None),
+        }
+    }
+}
+
+pub fn parse_args(command: &CliCommand, args: &[String]) -> Result<ParsedArgs, CliError> {
+    let mut parsed = ParsedArgs::default();
+    let mut iter = args.iter();
+    let mut only_positionals = false;
+
+    while let Some(arg) = iter.next() {
+        if only_positionals || !arg.starts_with('-') || arg == "-" {
+            parsed.positionals.push(arg.clone());
+            continue;
+        }
+        if arg == "--" {
+            only_positionals = true;
+            continue;
+        }
+
+        let (key, inline_value) = match arg.split_once('=') {
+            Some((key, value)) if key.starts_with("--") => (key, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let key = if key == "-h" { CLI_ARG_HELP.key } else { key };
+
+        let spec = command
+            .args
+            .iter()
+            .find(|a| a.key == key)
+            .ok_or_else(|| CliError::usage(format!(
+                "Unknown option '{}' for '{}'. See '{} --help'.", key, command.name, command.name
+            )))?;
+
+        if spec.flag {
+            if inline_value.is_some() {
+                return Err(CliError::usage(format!("Flag {} does not take a value", spec.key)));
+            }
+            parsed.flags.push(spec.key);
+        } else {
+            let value = match inline_value {
+                Some(value) => value,
+                None => iter
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| CliError::usage(format!("Option {} must have a value", spec.key)))?,
+            };
+            parsed.values.push((spec.key, value));
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Removes `--log-level <FILTER>` from `args`, wherever it appears before `--`, and
+/// returns the last filter given.
+pub fn take_log_level(args: &mut Vec<String>) -> Result<Option<LogFilter>, CliError> {
+    let mut filter = None;
+    let mut index = 0;
+    while index < args.len() {
+        let arg = &args[index];
+        if arg == "--" {
+            break;
+        }
+        let value = if arg == CLI_ARG_LOG_LEVEL.key {
+            if index + 1 >= args.len() {
+                return Err(CliError::usage(format!("Option {} must have a value", CLI_ARG_LOG_LEVEL.key)));
+            }
+            args.remove(index);
+            args.remove(index)
+        } else if let Some(value) = arg.strip_prefix(CLI_ARG_LOG_LEVEL.key).and_then(|rest| rest.strip_prefix('=')) {
+            let value = value.to_string();
+            args.remove(index);
+            value
+        } else {
+            index += 1;
+            continue;
+        };
+        filter = Some(
+            value
+                .parse::<LogFilter>()
+                .map_err(|e| CliError::usage(format!("Invalid value '{}' for {}: {}", value, CLI_ARG_LOG_LEVEL.key, e)))?,
+        );
+    }
+    Ok(filter)
+}
+
+pub fn print_command_help(command: &CliCommand) {
+    println!("Usage: {} {}", env!("CARGO_PKG_NAME"), command.usage);
+    println!();
+    println!("{}", command.description);
+    println!();
+    println!("Options:");
+    for arg in command.args {
+        let requires_value = if arg.flag { "" } else { "[VALUE]" };
+        println!(" {:18} {:8} {}", arg.key, requires_value, arg.description);
+    }
+}
+
+fn print_help() {
+    println!("Usage: {} <COMMAND> [OPTIONS]", env!("CARGO_PKG_NAME"));
+    println!("Run without arguments to start the graphical interface.");
+    println!();
+    println!("Commands:");
+    for command in COMMANDS {
+        println!(" {:10} {}", command.name, command.description);
+    }
+    println!(" {:10} Print this help, or help for a command.", "help");
+    println!();
+    println!("Global options:");
+    println!(" {:18} {:8} {}", CLI_ARG_LOG_LEVEL.key, "[VALUE]", CLI_ARG_LOG_LEVEL.description);
+    println!();
+    println!("Run '{} <COMMAND> --help' for the options of a command.", env!("CARGO_PKG_NAME"));
+    println!();
+    println!("Exit codes:");
+    for (code, description) in [
+        (EXIT_SUCCESS, "Success"),
+        (EXIT_FAILURE, "Other failure"),
+        (EXIT_USAGE, "Invalid command line"),
+        (EXIT_NOT_FOUND, "Replay not found or expired"),
+        (EXIT_NOT_RECORDED, "Replay not recorded yet (match still running)"),
+        (EXIT_NETWORK, "Network failure or server error"),
+        (EXIT_PARSE, "Invalid server response or replay data"),
+        (EXIT_WRITE, "Failed to write output"),
+    ] {
+        println!(" {:<3} {}", code, description);
+    }
+}
+
+/// Applies the network options shared by all commands that talk to the API.
+pub fn configure_network(args: &ParsedArgs) -> CliResult {
+    let mut network = ReplayApp::load_settings().unwrap_or_default().network;
+    if let Some(proxy) = args.value(CLI_ARG_PROXY.key) {
+        network.proxy_url = proxy.to_string();
+    }
+    if let Some(no_proxy) = args.value(CLI_ARG_NO_PROXY.key) {
+        network.no_proxy = no_proxy.to_string();
+    }
+    network.extra_root_certs.extend(args.values(CLI_ARG_CA_CERT.key).into_iter().map(PathBuf::from));
+    network.accept_invalid_certs |= args.flag(CLI_ARG_INSECURE.key);
+
+    http_client::configure(&network)
+        .map_err(|e| CliError::usage(format!("Invalid network settings: {}", e)))
+}
+
+/// Runs the command line interface and returns the process exit code.
+pub fn run(args: Vec<String>) -> i32 {
+    let Some(first) = args.first() else {
+        print_help();
+        return EXIT_USAGE;
+    };
+
+    let (command, rest) = match first.as_str() {
+        "help" | "-h" | "--help" => {
+            match args.get(1).and_then(|name| COMMANDS.iter().find(|c| c.name == name)) {
+                Some(command) => print_command_help(command),
+                None => print_help(),
+            }
+            return EXIT_SUCCESS;
+        }
+        // Pre-subcommand invocations (`-r <ID> [-o FILE] [--alt]...`) keep working as `download`.
+        name if name.starts_with('-') => (&download::COMMAND, &args[..]),
+        name => match COMMANDS.iter().find(|c| c.name == name) {
+            Some(command) => (*command, &args[1..]),
+            None => {
+                eprintln!("Unknown command '{}'. Run '{} help' for a list of commands.", name, env!("CARGO_PKG_NAME"));
+                return EXIT_USAGE;
+            }
+        },
+    };
+
+    let parsed = match parse_args(command, rest) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return e.code;
+        }
+    };
+
+    if parsed.flag(CLI_ARG_HELP.key) {
+        print_command_help(command);
+        return EXIT_SUCCESS;
+    }
+
+    match (command.run)(&parsed) {
+        Ok(()) => EXIT_SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            e.code
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_flags_values_and_positionals() {
+        let parsed = parse_args(
+            &info::COMMAND,
+            &args(&["abc123", "--insecure", "--proxy", "http://proxy:8080", "--ca-cert=a.pem", "--ca-cert", "b.pem", "-"]),
+        )
+        .unwrap();
+        assert_eq!(parsed.positionals, ["abc123", "-"]);
+        assert!(parsed.flag(CLI_ARG_INSECURE.key));
+        assert!(!parsed.flag(CLI_ARG_HELP.key));
+        assert_eq!(parsed.value(CLI_ARG_PROXY.key), Some("http://proxy:8080"));
+        assert_eq!(parsed.values(CLI_ARG_CA_CERT.key), ["a.pem", "b.pem"]);
+        assert_eq!(parsed.value(CLI_ARG_CA_CERT.key), Some("b.pem"));
+    }
+
+    #[test]
+    fn short_help_and_double_dash() {
+        let parsed = parse_args(&info::COMMAND, &args(&["-h", "--", "--insecure"])).unwrap();
+        assert!(parsed.flag(CLI_ARG_HELP.key));
+        assert!(!parsed.flag(CLI_ARG_INSECURE.key));
+        assert_eq!(parsed.positionals, ["--insecure"]);
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        for bad in [&["--unknown"][..], &["--proxy"], &["--insecure=yes"]] {
+            let err = parse_args(&info::COMMAND, &args(bad)).err().unwrap();
+            assert_eq!(err.code, EXIT_USAGE, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn parsed_value_reports_invalid_values() {
+        let parsed = parse_args(&info::COMMAND, &args(&["--proxy", "x"])).unwrap();
+        assert_eq!(parsed.parsed_value::<String>(CLI_ARG_PROXY.key).unwrap().as_deref(), Some("x"));
+        assert!(parsed.parsed_value::<u32>(CLI_ARG_PROXY.key).is_err());
+        assert_eq!(parsed.parsed_value::<u32>(CLI_ARG_CA_CERT.key).unwrap(), None);
+    }
+
+    #[test]
+    fn takes_the_last_log_level_anywhere_before_double_dash() {
+        let mut rest = args(&["--log-level", "warn", "download", "--log-level=debug", "abc", "--", "--log-level", "x"]);
+        let filter = take_log_level(&mut rest).unwrap();
+        assert_eq!(filter, Some("debug".parse().unwrap()));
+        assert_eq!(rest, ["download", "abc", "--", "--log-level", "x"]);
+    }
+
+    #[test]
+    fn log_level_is_optional() {
+        let mut rest = args(&["list", "--json"]);
+        assert_eq!(take_log_level(&mut rest).unwrap(), None);
+        assert_eq!(rest, ["list", "--json"]);
+    }
+
+    #[test]
+    fn rejects_missing_or_invalid_log_levels() {
+        for bad in [&["list", "--log-level"][..], &["--log-level=loud"], &["--log-level", "info,tools="]] {
+            let err = take_log_level(&mut args(bad)).err().unwrap();
+            assert_eq!(err.code, EXIT_USAGE, "{:?}", bad);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use crate::cli::download::{naming_options, CLI_ARG_NAME_TEMPLATE, CLI_ARG_UTC};
+use crate::cli::{exit_code, CliArg, CliCommand, CliError, CliResult, Console, ParsedArgs, CLI_ARG_HELP, EXIT_FAILURE};
+use crate::tools::build_replay::{build_replay, write_replay};
+use crate::tools::error::{ErrorKind, ReplayError};
+use crate::tools::library::write_replay_file;
+use crate::tools::naming::{local_replay_filename, NamingOptions};
+use crate::tools::replay_processor::{load_replay_parts, replay_chunks_dir, Config, Progress};
+
+pub const CLI_ARG_OUTPUT: CliArg = CliArg {
+    key: "-o",
+    flag: false,
+    description: "Output file, or '-' to write the replay to stdout. Only valid with a single input directory.",
+};
+pub const CLI_ARG_DIR: CliArg = CliArg {
+    key: "--dir",
+    flag: false,
+    description: "Output directory for generated file names. Defaults to the current directory.",
+};
+pub const CLI_ARG_DATA_COUNT: CliArg = CliArg {
+    key: "--data-count",
+    flag: false,
+    description: "Maximum number of data (stream) chunks to include.",
+};
+pub const CLI_ARG_EVENT_COUNT: CliArg = CliArg {
+    key: "--event-count",
+    flag: false,
+    description: "Maximum number of event chunks to include.",
+};
+pub const CLI_ARG_CHECKPOINT_COUNT: CliArg = CliArg {
+    key: "--checkpoint-count",
+    flag: false,
+    description: "Maximum number of checkpoint chunks to include.",
+};
+pub const CLI_ARG_JSON: CliArg = CliArg {
+    key: "--json",
+    flag: true,
+    description: "Print progress and results as one JSON object per line.",
+};
+
+pub const COMMAND: CliCommand = CliCommand {
+    name: "process",
+    usage: "process [CHUNKS_DIR]... [-o FILE | --dir DIR] [--name-template T] [--utc] [--data-count N] [--event-count N] [--checkpoint-count N] [--json]",
+    description: "Build .replay files from local replay_chunks directories (legacy format).",
+    args: &[
+        CLI_ARG_OUTPUT, CLI_ARG_DIR, CLI_ARG_NAME_TEMPLATE, CLI_ARG_UTC, CLI_ARG_DATA_COUNT, CLI_ARG_EVENT_COUNT, CLI_ARG_CHECKPOINT_COUNT,
+        CLI_ARG_JSON, CLI_ARG_HELP,
+    ],
+    run,
+};
+
+fn progress_line(progress: &Progress) -> String {
+    format!(
+        "header {}/{}, data {}/{}, events {}/{}, checkpoints {}/{}",
+        progress.header.current, progress.header.max,
+        progress.data_chunks.current, progress.data_chunks.max,
+        progress.event_chunks.current, progress.event_chunks.max,
+        progress.checkpoint_chunks.current, progress.checkpoint_chunks.max,
+    )
+}
+
+fn config_from_args(args: &ParsedArgs, input: &Path, console: Console) -> Result<Config, CliError> {
+    let defaults = Config::default();
+    let input = input.display().to_string();
+
+    Ok(Config {
+        update_callback: Box::new(move |progress| {
+            if console.json {
+                console.print(json!({ "event": "progress", "input": input, "progress": progress }));
+            } else if console.stderr {
+                eprint!("\r  {}", progress_line(&progress));
+            } else {
+                print!("\r  {}", progress_line(&progress));
+                let _ = std::io::stdout().flush();
+            }
+        }),
+        data_count: args.parsed_value(CLI_ARG_DATA_COUNT.key)?.unwrap_or(defaults.data_count),
+        event_count: args.parsed_value(CLI_ARG_EVENT_COUNT.key)?.unwrap_or(defaults.event_count),
+        checkpoint_count: args.parsed_value(CLI_ARG_CHECKPOINT_COUNT.key)?.unwrap_or(defaults.checkpoint_count),
+    })
+}
+
+fn process_one(
+    args: &ParsedArgs,
+    input: &Path,
+    output_dir: &Path,
+    naming: &NamingOptions,
+    console: Console,
+    config: Config,
+) -> Result<(PathBuf, usize), ReplayError> {
+    let process_error = |e: &dyn std::fmt::Display| {
+        ReplayError::new(ErrorKind::Parse, format!("Failed to process {}: {}", input.display(), e))
+    };
+    let (parts, meta) = load_replay_parts(input, Some(config)).map_err(|e| process_error(&e))?;
+    if !console.json {
+        console.print("");
+    }
+
+    let output = args.value(CLI_ARG_OUTPUT.key);
+    if output == Some("-") {
+        let bytes = write_replay(&parts, &mut std::io::stdout().lock())
+            .map_err(|e| ReplayError::new(ErrorKind::Write, format!("Failed to write replay to stdout: {}", e)))?;
+        return Ok((PathBuf::from("-"), bytes));
+    }
+    let data = build_replay(&parts).map_err(|e| process_error(&e))?;
+    let output_file = match output {
+        Some(output) => PathBuf::from(output),
+        None => output_dir.join(
+            local_replay_filename(&meta, &data, naming).map_err(|e| ReplayError::new(ErrorKind::Parse, e.to_string()))?,
+        ),
+    };
+    write_replay_file(&output_file, &data)
+        .map_err(|e| ReplayError::new(ErrorKind::Write, format!("Failed to save replay file: {}", e)))?;
+    Ok((output_file, data.len()))
+}
+
+fn run(args: &ParsedArgs) -> CliResult {
+    let inputs: Vec<PathBuf> = if args.positionals.is_empty() {
+        vec![replay_chunks_dir()]
+    } else {
+        args.positionals.iter().map(PathBuf::from).collect()
+    };
+    if args.value(CLI_ARG_OUTPUT.key).is_some() {
+        if inputs.len() > 1 {
+            return Err(CliError::usage("-o can only be used with a single input directory"));
+        }
+        if args.value(CLI_ARG_DIR.key).is_some() {
+            return Err(CliError::usage("-o and --dir are mutually exclusive"));
+        }
+    }
+    let output_dir = match args.value(CLI_ARG_DIR.key) {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::current_dir()
+            .map_err(|e| CliError::failure(format!("Failed to read working directory: {}", e)))?,
+    };
+    let naming = naming_options(args)?;
+    let console = Console {
+        json: args.flag(CLI_ARG_JSON.key),
+        stderr: args.value(CLI_ARG_OUTPUT.key) == Some("-"),
+    };
+
+    let mut failures: Vec<ErrorKind> = Vec::new();
+    for input in &inputs {
+        if !console.json {
+            console.print(format!("Processing '{}'...", input.display()));
+        }
+        let config = config_from_args(args, input, console)?;
+        match process_one(args, input, &output_dir, &naming, console, config) {
+            Ok((output, bytes)) => {
+                if console.json {
+                    console.print(json!({ "event": "done", "input": input, "output": output, "bytes": bytes }));
+                } else {
+                    console.print(if output == Path::new("-") {
+                        format!("Wrote {} bytes to stdout.", bytes)
+                    } else {
+                        format!("Saved '{}' ({} bytes).", output.display(), bytes)
+                    });
+                }
+            }
+            Err(e) if console.json => {
+                failures.push(e.kind);
+                console.print(json!({
+                    "event": "error",
+                    "input": input,
+                    "kind": e.kind,
+                    "exit_code": exit_code(e.kind),
+                    "message": e.message,
+                }));
+            }
+            Err(e) if inputs.len() == 1 => return Err(e.into()),
+            Err(e) => {
+                failures.push(e.kind);
+                eprintln!("Error: {}", e);
+            }
+        }
+    }
+
+    let Some(&kind) = failures.first() else {
+        return Ok(());
+    };
+    let code = if failures.iter().all(|k| *k == kind) { exit_code(kind) } else { EXIT_FAILURE };
+    Err(CliError {
+        code,
+        message: format!("{} of {} directories failed", failures.len(), inputs.len()),
+    })
+}
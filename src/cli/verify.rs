@@ -0,0 +1,41 @@
+use std::fs;
+
+use crate::cli::{CliCommand, CliError, CliResult, ParsedArgs, CLI_ARG_HELP};
+use crate::tools::replay_reader::read_replay_summary;
+
+pub const COMMAND: CliCommand = CliCommand {
+    name: "verify",
+    usage: "verify <FILE.replay>...",
+    description: "Check that .replay files are complete and structurally valid.",
+    args: &[CLI_ARG_HELP],
+    run,
+};
+
+fn run(args: &ParsedArgs) -> CliResult {
+    if args.positionals.is_empty() {
+        return Err(CliError::usage("Missing replay file. See 'verify --help'."));
+    }
+
+    let mut failed = 0;
+    for file in &args.positionals {
+        let result = fs::read(file)
+            .map_err(|e| e.to_string())
+            .and_then(|data| read_replay_summary(&data).map_err(|e| e.to_string()));
+
+        match result {
+            Ok(summary) => println!(
+                "OK      {} ({} bytes, {} data, {} checkpoint, {} event chunks)",
+                file, summary.file_size, summary.data_chunks, summary.checkpoint_chunks, summary.event_chunks
+            ),
+            Err(e) => {
+                failed += 1;
+                println!("INVALID {}: {}", file, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(CliError::failure(format!("{} of {} files failed verification", failed, args.positionals.len())));
+    }
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use chrono::Local;
+
+use crate::app::{ReplayApp, Settings};
+use crate::cli::{
+    configure_network, exit_code, CliArg, CliCommand, CliError, CliResult, ParsedArgs, CLI_ARG_CA_CERT,
+    CLI_ARG_HELP, CLI_ARG_INSECURE, CLI_ARG_NO_PROXY, CLI_ARG_PROXY, EXIT_FAILURE, EXIT_WRITE,
+};
+use crate::tools::auto_download::{plan_downloads, AutoDownloadPlan};
+use crate::tools::batch::{download_listed, run_batch, BatchOptions, BatchSummary, DEFAULT_JOBS};
+use crate::tools::library::scan_downloaded_ids;
+use crate::tools::listing::{for_each_page, ListQuery};
+use crate::tools::replay_processor::ApiReplay;
+use crate::tools::script::ScriptHost;
+
+/// Failed downloads are retried on later polls until they have failed this many times.
+const MAX_ATTEMPTS: u32 = 3;
+
+pub const CLI_ARG_INTERVAL: CliArg = CliArg {
+    key: "--interval",
+    flag: false,
+    description: "Seconds between polls. Defaults to the auto-refresh interval in settings.",
+};
+pub const CLI_ARG_PAGES: CliArg = CliArg {
+    key: "--pages",
+    flag: false,
+    description: "Number of listing pages checked on each poll (default 1).",
+};
+pub const CLI_ARG_DIR: CliArg = CliArg {
+    key: "--dir",
+    flag: false,
+    description: "Download directory. Overrides the download directory in settings.",
+};
+pub const CLI_ARG_LOG: CliArg = CliArg {
+    key: "--log",
+    flag: false,
+    description: "Also append log lines to this file.",
+};
+pub const CLI_ARG_ONCE: CliArg = CliArg {
+    key: "--once",
+    flag: true,
+    description: "Poll a single time and exit (for cron or systemd timers). Exits with the download exit code if anything failed.",
+};
+
+pub const COMMAND: CliCommand = CliCommand {
+    name: "watch",
+    usage: "watch [--interval SECS] [--pages N] [--dir DIR] [--log FILE] [--once]",
+    description: "Poll PavlovTV and download replays matching the auto-download settings and watched replays about to expire.",
+    args: &[
+        CLI_ARG_INTERVAL, CLI_ARG_PAGES, CLI_ARG_DIR, CLI_ARG_LOG, CLI_ARG_ONCE,
+        CLI_ARG_PROXY, CLI_ARG_NO_PROXY, CLI_ARG_CA_CERT, CLI_ARG_INSECURE, CLI_ARG_HELP,
+    ],
+    run,
+};
+
+/// Timestamped log lines on stdout and, optionally, in a file.
+struct WatchLog {
+    file: Option<File>,
+}
+
+impl WatchLog {
+    fn open(path: Option<&str>) -> Result<Self, CliError> {
+        let file = match path {
+            Some(path) => Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| CliError::failure(format!("Failed to open log file {}: {}", path, e)))?,
+            ),
+            None => None,
+        };
+        Ok(Self { file })
+    }
+
+    fn log(&mut self, message: impl AsRef<str>) {
+        let line = format!("[{}] {}", Local::now().format("%Y-%m-%d %H:%M:%S"), message.as_ref());
+        println!("{}", line);
+        if let Some(file) = self.file.as_mut() {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+struct WatchState {
+    download_dir: PathBuf,
+    pages: usize,
+    failures: HashMap<String, u32>,
+    script: ScriptHost,
+    /// Replays the download script already failed on, so each error is logged once.
+    script_failures: HashSet<String>,
+    /// Exit codes of everything that failed during the current poll.
+    poll_failures: Vec<i32>,
+}
+
+/// Replays on the first `pages` pages of the listing that an auto-download rule or the
+/// download script selects.
+fn poll_listing(state: &WatchState, settings: &Settings) -> Result<AutoDownloadPlan, CliError> {
+    let mut listed: Vec<ApiReplay> = Vec::new();
+    let mut remaining = state.pages;
+    for_each_page(&ListQuery::default(), |replays, _, _| {
+        listed.extend(replays);
+        remaining -= 1;
+        remaining > 0
+    })?;
+    Ok(plan_downloads(&settings.auto_download_rules, state.script.script(), &state.download_dir, listed))
+}
+
+impl WatchState {
+    fn options(&self, settings: &Settings, download_dir: &Path, file_names: HashMap<String, PathBuf>) -> BatchOptions {
+        BatchOptions {
+            download_dir: download_dir.to_path_buf(),
+            naming: settings.naming.clone(),
+            jobs: DEFAULT_JOBS,
+            skip_existing: true,
+            file_names,
+        }
+    }
+
+    fn should_try(&self, id: &str, existing: &HashSet<String>) -> bool {
+        !existing.contains(id) && self.failures.get(id).copied().unwrap_or(0) < MAX_ATTEMPTS
+    }
+
+    /// Logs the outcome of a download round and counts failed attempts.
+    fn record(&mut self, summary: Result<BatchSummary, Box<dyn std::error::Error + Send + Sync>>, log: &mut WatchLog) {
+        match summary {
+            Ok(summary) => {
+                for (id, path) in &summary.downloaded {
+                    log.log(format!("Downloaded {} to '{}'", id, path.display()));
+                }
+                for (id, error) in summary.failed {
+                    self.poll_failures.push(exit_code(error.kind));
+                    let attempts = self.failures.entry(id.clone()).or_insert(0);
+                    *attempts += 1;
+                    log.log(format!("Failed {} (attempt {} of {}): {}", id, attempts, MAX_ATTEMPTS, error));
+                }
+            }
+            Err(e) => {
+                let error = CliError::from(e);
+                log.log(format!("Download failed: {}", error));
+                self.poll_failures.push(error.code);
+            }
+        }
+    }
+
+    /// Exit code for the failures of the last poll, like `download` reports a batch: the
+    /// shared code if every failure had the same cause, a general failure otherwise.
+    fn poll_failure(&self) -> Option<CliError> {
+        let code = *self.poll_failures.first()?;
+        let code = if self.poll_failures.iter().all(|&other| other == code) { code } else { EXIT_FAILURE };
+        Some(CliError { code, message: format!("{} failure(s) during the poll, see the log above", self.poll_failures.len()) })
+    }
+}
+
+fn poll(state: &mut WatchState, settings: &Settings, log: &mut WatchLog) {
+    state.poll_failures.clear();
+    let rules_enabled = settings.has_active_rules();
+    let watchlist_enabled = settings.watchlist_auto_download && !settings.watchlist.is_empty();
+    if !rules_enabled && !watchlist_enabled {
+        log.log("Auto-download is disabled or has no active rules, and the watchlist is empty. Nothing to do.");
+        return;
+    }
+
+    if rules_enabled {
+        poll_rules(state, settings, log);
+    }
+    if watchlist_enabled {
+        poll_watchlist(state, settings, log);
+    }
+}
+
+/// Downloads watched replays that expire within the configured window.
+fn poll_watchlist(state: &mut WatchState, settings: &Settings, log: &mut WatchLog) {
+    let existing: HashSet<String> = scan_downloaded_ids(&state.download_dir, settings.naming.folder_depth());
+    let due: Vec<String> = settings.watchlist
+        .iter()
+        .filter(|entry| entry.is_due(settings.watchlist_expiry_window_hours))
+        .filter(|entry| state.should_try(&entry.id, &existing))
+        .map(|entry| entry.id.clone())
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    log.log(format!(
+        "{} watched replay(s) expire within {} hour(s), downloading.",
+        due.len(), settings.watchlist_expiry_window_hours
+    ));
+    let summary = run_batch(&due, &state.options(settings, &state.download_dir, HashMap::new()), &|_| {});
+    state.record(summary, log);
+}
+
+fn poll_rules(state: &mut WatchState, settings: &Settings, log: &mut WatchLog) {
+    match state.script.refresh(&settings.auto_download_script) {
+        Some(Ok(())) => log.log(format!("Loaded download script '{}'.", settings.auto_download_script.display())),
+        Some(Err(e)) => {
+            log.log(format!("Download script error: {}", e));
+            state.poll_failures.push(EXIT_FAILURE);
+        }
+        None => {}
+    }
+
+    let mut plan = match poll_listing(state, settings) {
+        Ok(plan) => plan,
+        Err(e) => {
+            log.log(format!("Error loading replays: {}", e));
+            state.poll_failures.push(e.code);
+            return;
+        }
+    };
+
+    for (id, error) in plan.script_errors.drain(..) {
+        state.poll_failures.push(EXIT_FAILURE);
+        if state.script_failures.insert(id.clone()) {
+            log.log(format!("Download script failed on {}: {}", id, error));
+        }
+    }
+
+    let mut found_any = false;
+    for (dir, replays) in plan.jobs {
+        if let Err(e) = fs::create_dir_all(&dir) {
+            log.log(format!("Failed to create download directory {}: {}", dir.display(), e));
+            state.poll_failures.push(EXIT_WRITE);
+            continue;
+        }
+        let existing: HashSet<String> = scan_downloaded_ids(&dir, settings.naming.folder_depth());
+        let pending: Vec<ApiReplay> = replays
+            .into_iter()
+            .filter(|replay| state.should_try(&replay.id, &existing))
+            .collect();
+        if pending.is_empty() {
+            continue;
+        }
+
+        found_any = true;
+        log.log(format!("Found {} new replay(s) for '{}'.", pending.len(), dir.display()));
+        let file_names = pending
+            .iter()
+            .filter_map(|replay| plan.file_names.get(&replay.id).map(|name| (replay.id.clone(), name.clone())))
+            .collect();
+        let summary = download_listed(pending, &state.options(settings, &dir, file_names), &|_| {});
+        state.record(summary, log);
+    }
+
+    if !found_any {
+        log.log("No new replays to download.");
+    }
+}
+
+fn run(args: &ParsedArgs) -> CliResult {
+    let interval = args.parsed_value::<u64>(CLI_ARG_INTERVAL.key)?;
+    if interval == Some(0) {
+        return Err(CliError::usage("--interval must be at least 1"));
+    }
+    let pages = args.parsed_value::<usize>(CLI_ARG_PAGES.key)?.unwrap_or(1);
+    if pages == 0 {
+        return Err(CliError::usage("--pages must be at least 1"));
+    }
+    let mut log = WatchLog::open(args.value(CLI_ARG_LOG.key))?;
+    configure_network(args)?;
+
+    let mut settings = ReplayApp::load_settings()
+        .map_err(|e| CliError::failure(format!("Failed to load settings: {}", e)))?;
+    let mut state = WatchState {
+        download_dir: PathBuf::new(),
+        pages,
+        failures: HashMap::new(),
+        script: ScriptHost::default(),
+        script_failures: HashSet::new(),
+        poll_failures: Vec::new(),
+    };
+
+    log.log(format!(
+        "Watching PavlovTV with {} auto-download rule(s) and {} watched replay(s).",
+        settings.auto_download_rules.iter().filter(|rule| rule.enabled).count(),
+        settings.watchlist.len()
+    ));
+    loop {
+        state.download_dir = args
+            .value(CLI_ARG_DIR.key)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| settings.download_dir.clone());
+        if let Err(e) = fs::create_dir_all(&state.download_dir) {
+            return Err(CliError::failure(format!(
+                "Failed to create download directory {}: {}", state.download_dir.display(), e
+            )));
+        }
+
+        poll(&mut state, &settings, &mut log);
+        if args.flag(CLI_ARG_ONCE.key) {
+            return state.poll_failure().map_or(Ok(()), Err);
+        }
+
+        let secs = interval.unwrap_or(settings.auto_refresh_interval_mins.max(1) * 60);
+        thread::sleep(Duration::from_secs(secs));
+
+        // Pick up trigger changes made in the GUI without restarting the service.
+        match ReplayApp::load_settings() {
+            Ok(reloaded) => settings = reloaded,
+            Err(e) => log.log(format!("Failed to reload settings, keeping previous ones: {}", e)),
+        }
+    }
+}
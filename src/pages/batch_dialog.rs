@@ -0,0 +1,103 @@
+use eframe::egui::{self, Context};
+
+use crate::app::{BatchEntryStatus, ReplayApp};
+
+pub fn render_batch_dialog(app: &mut ReplayApp, ctx: &Context) {
+    if !app.batch.open {
+        return;
+    }
+
+    let mut open = app.batch.open;
+    let mut start_clicked = false;
+
+    egui::Window::new("Batch Download")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([420.0, 420.0])
+        .show(ctx, |ui| {
+            ui.label("Replay IDs (one per line, or separated by spaces or commas):");
+            ui.add_enabled(
+                !app.batch.running,
+                egui::TextEdit::multiline(&mut app.batch.ids_text)
+                    .desired_rows(6)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("# Lines starting with # are ignored"),
+            );
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!app.batch.running, egui::Button::new("Load from file")).clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Text", &["txt", "csv"])
+                        .pick_file()
+                    {
+                        match std::fs::read_to_string(&path) {
+                            Ok(text) => app.batch.ids_text = text,
+                            Err(e) => app.show_error(format!("Failed to read {}: {}", path.display(), e)),
+                        }
+                    }
+                }
+
+                ui.add_enabled(
+                    !app.batch.running,
+                    egui::Slider::new(&mut app.batch.jobs, 1..=8).text("Parallel downloads"),
+                );
+            });
+
+            ui.label(format!("Saving to: {}", app.settings.download_dir.display()));
+            ui.add_space(4.0);
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!app.batch.running, egui::Button::new("Start")).clicked() {
+                    start_clicked = true;
+                }
+                if app.batch.running {
+                    ui.spinner();
+                    ui.label("Downloading...");
+                }
+            });
+
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    if let Ok(entries) = app.batch.entries.lock() {
+                        for (id, status) in entries.iter() {
+                            ui.horizontal(|ui| {
+                                ui.monospace(id);
+                                match status {
+                                    BatchEntryStatus::Queued => {
+                                        ui.weak("Queued");
+                                    }
+                                    BatchEntryStatus::Downloading { current, total } => {
+                                        let progress = if *total > 0 {
+                                            *current as f32 / *total as f32
+                                        } else {
+                                            0.0
+                                        };
+                                        ui.add(egui::ProgressBar::new(progress).show_percentage().animate(true));
+                                    }
+                                    BatchEntryStatus::Done(path) => {
+                                        ui.colored_label(egui::Color32::from_rgb(100, 200, 100), "Done")
+                                            .on_hover_text(path.display().to_string());
+                                    }
+                                    BatchEntryStatus::Skipped(reason) => {
+                                        ui.weak(format!("Skipped: {}", reason));
+                                    }
+                                    BatchEntryStatus::Failed(error) => {
+                                        ui.colored_label(ui.visuals().error_fg_color, format!("Failed: {}", error));
+                                    }
+                                }
+                            });
+                        }
+                    }
+                });
+        });
+
+    app.batch.open = open;
+    if start_clicked {
+        app.start_batch_download();
+    }
+}
@@ -0,0 +1,62 @@
+use eframe::egui::{self, Context};
+
+use crate::app::ReplayApp;
+use crate::tools::address_book::Contact;
+
+pub fn render_contact_dialog(app: &mut ReplayApp, ctx: &Context) {
+    let Some((user_id, mut contact)) = app.contact_editor.take() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut save = false;
+    let mut close = false;
+
+    egui::Window::new("Edit Contact")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("User ID:");
+                ui.monospace(&user_id);
+            });
+            ui.add_space(4.0);
+
+            egui::Grid::new("contact_fields").num_columns(2).show(ui, |ui| {
+                ui.label("Nickname:");
+                ui.add(egui::TextEdit::singleline(&mut contact.nickname).desired_width(240.0));
+                ui.end_row();
+
+                ui.label("Team tag:");
+                ui.add(egui::TextEdit::singleline(&mut contact.team).desired_width(240.0));
+                ui.end_row();
+
+                ui.label("Notes:");
+                ui.add(egui::TextEdit::multiline(&mut contact.notes)
+                    .desired_width(240.0)
+                    .desired_rows(4));
+                ui.end_row();
+            });
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    save = true;
+                }
+                if ui.button("Remove").clicked() {
+                    contact = Contact::default();
+                    save = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if save {
+        app.save_contact(&user_id, contact);
+    } else if open && !close {
+        app.contact_editor = Some((user_id, contact));
+    }
+}
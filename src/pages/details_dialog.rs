@@ -0,0 +1,218 @@
+use eframe::egui::{self, Context};
+
+use crate::app::ReplayApp;
+use crate::tools::live::elapsed_text;
+use crate::tools::replay_processor::{parse_mod_ids, MetaData, ReplayItem};
+use crate::tools::watchlist::{add_to_watchlist, expiry_text, time_until_expiry};
+
+pub fn render_details_dialog(app: &mut ReplayApp, ctx: &Context) {
+    let Some(details) = &app.details else {
+        return;
+    };
+    let replay = details.replay.clone();
+    let meta = details.meta.clone();
+
+    let mut open = true;
+    egui::Window::new(format!("Replay Details - {}", replay.map_name))
+        .id(egui::Id::new("replay_details"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([520.0, 560.0])
+        .show(ctx, |ui| {
+            render_action_bar(app, ui, &replay);
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    render_listing_fields(ui, &replay);
+                    ui.add_space(8.0);
+                    render_meta(ui, meta.as_ref());
+                    ui.add_space(8.0);
+                    render_players(app, ui, ctx, &replay);
+                    ui.add_space(8.0);
+                    render_mods(app, ui, &replay);
+                });
+        });
+
+    if !open {
+        app.details = None;
+    }
+}
+
+fn render_action_bar(app: &mut ReplayApp, ui: &mut egui::Ui, replay: &ReplayItem) {
+    ui.horizontal(|ui| {
+        let is_downloading = app.downloading_replay_id.as_ref().is_some_and(|id| id == &replay.id);
+        if app.downloaded_replays.contains(&replay.id) {
+            ui.add_enabled(false, egui::Button::new("Downloaded"));
+        } else if is_downloading {
+            ui.add_enabled(false, egui::Button::new("Downloading..."));
+        } else if replay.live {
+            if app.live.is_awaiting(&replay.id) {
+                ui.add_enabled(false, egui::Button::new("Downloading when finished"));
+            } else if ui.button("Download when finished").clicked() {
+                app.download_when_finished(replay);
+            }
+        } else if ui.button("Download & Process").clicked() {
+            app.process_online_replay(&replay.id);
+        }
+
+        let watched = app.settings.watchlist.iter().any(|entry| entry.id == replay.id);
+        if watched {
+            ui.add_enabled(false, egui::Button::new("Watched"));
+        } else if ui.button("Add to watchlist").clicked() {
+            add_to_watchlist(&mut app.settings.watchlist, [replay]);
+            if let Err(err) = app.save_settings() {
+                app.show_error(format!("Error saving settings: {}", err));
+            } else {
+                app.show_success("Added replay to the watchlist");
+            }
+        }
+
+        if ui.button("Copy ID").clicked() {
+            ui.ctx().copy_text(replay.id.clone());
+            app.show_success(format!("Copied replay ID: {}", replay.id));
+        }
+    });
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "Yes" } else { "No" }
+}
+
+fn render_listing_fields(ui: &mut egui::Ui, replay: &ReplayItem) {
+    ui.strong("Listing");
+    egui::Grid::new("details_listing").num_columns(2).striped(true).show(ui, |ui| {
+        ui.label("ID:");
+        ui.monospace(&replay.id);
+        ui.end_row();
+        ui.label("Map:");
+        ui.label(&replay.map_name);
+        ui.end_row();
+        ui.label("Game mode:");
+        ui.label(&replay.game_mode);
+        ui.end_row();
+        ui.label("Platform:");
+        ui.label(if replay.shack { "Quest" } else { "PC" });
+        ui.end_row();
+        ui.label("Competitive:");
+        ui.label(yes_no(replay.competitive));
+        ui.end_row();
+        ui.label("Live:");
+        ui.label(yes_no(replay.live));
+        ui.end_row();
+        ui.label("Created:");
+        ui.label(&replay.created_date);
+        ui.end_row();
+        ui.label("Expires:");
+        match time_until_expiry(&replay.expires) {
+            Some(remaining) => ui.label(format!("{} ({})", replay.expires, expiry_text(remaining))),
+            None => ui.label(&replay.expires),
+        };
+        ui.end_row();
+        ui.label("Time since:");
+        ui.label(format!("{}s", replay.time_since));
+        ui.end_row();
+        ui.label("Players:");
+        ui.label(replay.users.len().to_string());
+        ui.end_row();
+        ui.label("Mods:");
+        ui.label(replay.modcount.to_string());
+        ui.end_row();
+    });
+}
+
+fn render_meta(ui: &mut egui::Ui, meta: Option<&Result<MetaData, String>>) {
+    ui.strong("Metadata");
+    match meta {
+        None => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Loading metadata...");
+            });
+        }
+        Some(Err(e)) => {
+            ui.colored_label(ui.style().visuals.error_fg_color, format!("Failed to load metadata: {}", e));
+        }
+        Some(Ok(meta)) => {
+            egui::Grid::new("details_meta").num_columns(2).striped(true).show(ui, |ui| {
+                ui.label("Total time:");
+                ui.label(format!(
+                    "{} ({} ms)",
+                    elapsed_text(chrono::TimeDelta::milliseconds(meta.total_time as i64)),
+                    meta.total_time
+                ));
+                ui.end_row();
+                ui.label("Version:");
+                ui.label(meta.version.to_string());
+                ui.end_row();
+                ui.label("Created:");
+                ui.label(&meta.created);
+                ui.end_row();
+                ui.label("Map:");
+                ui.label(&meta.friendly_name);
+                ui.end_row();
+                ui.label("Game mode:");
+                ui.label(&meta.game_mode);
+                ui.end_row();
+                ui.label("Competitive:");
+                ui.label(yes_no(meta.competitive));
+                ui.end_row();
+                ui.label("Live:");
+                ui.label(yes_no(meta.live));
+                ui.end_row();
+            });
+        }
+    }
+}
+
+fn render_players(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context, replay: &ReplayItem) {
+    ui.strong(format!("Players ({})", replay.users.len()));
+    if replay.users.is_empty() {
+        ui.label("No players recorded.");
+        return;
+    }
+    ui.horizontal_wrapped(|ui| {
+        for (idx, user) in replay.users.iter().enumerate() {
+            ui.push_id(idx, |ui| {
+                ui.vertical(|ui| {
+                    ui.set_width(72.0);
+                    app.render_user_avatar(ui, ctx, user);
+                    ui.add(egui::Label::new(app.settings.address_book.display_name(user)).truncate());
+                });
+            });
+        }
+    });
+}
+
+fn render_mods(app: &mut ReplayApp, ui: &mut egui::Ui, replay: &ReplayItem) {
+    let mod_ids = parse_mod_ids(&replay.workshop_mods);
+    ui.strong(format!("Workshop mods ({})", mod_ids.len()));
+    if mod_ids.is_empty() {
+        ui.label("No mods for this replay.");
+        return;
+    }
+    for mod_id in &mod_ids {
+        if !app.mod_info_cache.contains_key(mod_id) {
+            app.load_mod_info(mod_id.clone());
+        }
+        ui.horizontal(|ui| {
+            match app.mod_info_cache.get(mod_id) {
+                Some(info) if !info.is_loading && !info.failed => {
+                    ui.label(&info.name);
+                }
+                Some(info) if info.failed => {
+                    ui.colored_label(ui.style().visuals.error_fg_color, "Failed to load mod info");
+                }
+                _ => {
+                    ui.spinner();
+                }
+            }
+            ui.weak(mod_id);
+            if ui.small_button("mod.io").clicked() {
+                ui.ctx().open_url(egui::OpenUrl::new_tab(format!("https://mod.io/search/mods/{}", mod_id)));
+            }
+        });
+    }
+}
@@ -0,0 +1,85 @@
+use eframe::egui::{self, Context};
+
+use crate::app::{NotificationType, ReplayApp};
+
+pub fn render_history_dialog(app: &mut ReplayApp, ctx: &Context) {
+    if !app.history.open {
+        return;
+    }
+    app.history.unread_errors = 0;
+
+    let mut open = app.history.open;
+    egui::Window::new("Notification History")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([560.0, 400.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Show:");
+                egui::ComboBox::new(egui::Id::new("history_severity"), "")
+                    .selected_text(match app.history.min_severity {
+                        None => "Everything",
+                        Some(NotificationType::Warning) => "Warnings and errors",
+                        Some(NotificationType::Error) => "Errors only",
+                        Some(_) => "Everything",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut app.history.min_severity, None, "Everything");
+                        ui.selectable_value(&mut app.history.min_severity, Some(NotificationType::Warning), "Warnings and errors");
+                        ui.selectable_value(&mut app.history.min_severity, Some(NotificationType::Error), "Errors only");
+                    });
+                ui.add(egui::TextEdit::singleline(&mut app.history.filter)
+                    .hint_text("Filter")
+                    .desired_width(180.0));
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Clear").clicked() {
+                        app.history.entries.clear();
+                    }
+                    if ui.button("Log...").on_hover_text("Show the detailed application log").clicked() {
+                        app.log_viewer.open = true;
+                    }
+                    if ui.button("Copy").on_hover_text("Copy the shown entries").clicked() {
+                        let text: Vec<String> = app.history.filtered().iter().rev().map(|entry| entry.line()).collect();
+                        ui.ctx().copy_text(text.join("\n"));
+                    }
+                });
+            });
+            ui.separator();
+
+            let entries = app.history.filtered();
+            if entries.is_empty() {
+                ui.label("Nothing recorded yet.");
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    egui::Grid::new("history_entries").num_columns(3).striped(true).show(ui, |ui| {
+                        for entry in entries {
+                            ui.weak(entry.time.format("%m-%d %H:%M:%S").to_string());
+                            let color = match entry.severity {
+                                NotificationType::Error => ui.style().visuals.error_fg_color,
+                                NotificationType::Warning => ui.style().visuals.warn_fg_color,
+                                _ => ui.style().visuals.text_color(),
+                            };
+                            ui.colored_label(color, entry.severity.label());
+                            let message = if entry.from_status {
+                                format!("{} (status)", entry.message)
+                            } else {
+                                entry.message.clone()
+                            };
+                            if ui.add(egui::Label::new(message).wrap().sense(egui::Sense::click()))
+                                .on_hover_text("Click to copy")
+                                .clicked()
+                            {
+                                ui.ctx().copy_text(entry.line());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+        });
+    app.history.open = open;
+}
@@ -0,0 +1,126 @@
+use eframe::egui::{self, Context};
+
+use crate::app::ReplayApp;
+use crate::tools::live::{elapsed_since, elapsed_text};
+use crate::tools::replay_processor::ReplayItem;
+
+pub fn render_live_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
+    ui.horizontal(|ui| {
+        ui.heading("Live Matches");
+        if app.live.is_loading {
+            ui.spinner();
+        }
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.add_enabled(!app.live.is_loading, egui::Button::new("Refresh").min_size(egui::vec2(80.0, 32.0))).clicked() {
+                app.refresh_live_matches();
+            }
+            if let Some(last_refresh) = app.live.last_refresh {
+                ui.weak(format!("Updated {}s ago", last_refresh.elapsed().as_secs()));
+            }
+        });
+    });
+    ui.separator();
+
+    if let Some(error) = &app.live.error {
+        ui.colored_label(ui.style().visuals.error_fg_color, format!("Error loading live matches: {}", error));
+    }
+
+    if !app.live.awaiting.is_empty() {
+        ui.group(|ui| {
+            ui.label(format!("Waiting for {} match(es) to finish:", app.live.awaiting.len()));
+            let mut cancel = None;
+            for replay in &app.live.awaiting {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(&replay.map_name).strong());
+                    ui.label(&replay.game_mode);
+                    ui.weak(&replay.id);
+                    if ui.small_button("Cancel").clicked() {
+                        cancel = Some(replay.id.clone());
+                    }
+                });
+            }
+            if let Some(id) = cancel {
+                app.cancel_download_when_finished(&id);
+            }
+        });
+        ui.add_space(4.0);
+    }
+
+    let replays = app.live.replays.clone();
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            if replays.is_empty() {
+                ui.centered_and_justified(|ui| {
+                    if app.live.is_loading {
+                        ui.label("Loading live matches...");
+                    } else {
+                        ui.label("No matches are being recorded right now.");
+                    }
+                });
+                return;
+            }
+            for replay in &replays {
+                ui.push_id(replay.id.as_str(), |ui| {
+                    egui::Frame::group(ui.style())
+                        .fill(ui.style().visuals.extreme_bg_color)
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            render_live_match(app, ui, ctx, replay);
+                        });
+                });
+                ui.add_space(4.0);
+            }
+        });
+}
+
+fn render_live_match(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context, replay: &ReplayItem) {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new(&replay.map_name).size(20.0).strong());
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if app.live.is_awaiting(&replay.id) {
+                if ui.button("Cancel").clicked() {
+                    app.cancel_download_when_finished(&replay.id);
+                }
+                ui.label("Downloading when finished");
+            } else if ui.add(egui::Button::new("Download when finished").min_size(egui::vec2(160.0, 32.0))).clicked() {
+                app.download_when_finished(replay);
+            }
+            if ui.add(egui::Button::new("Details").min_size(egui::vec2(80.0, 32.0))).clicked() {
+                app.open_replay_details(replay);
+            }
+        });
+    });
+
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 4.0;
+        ui.label("Game Mode:");
+        ui.label(&replay.game_mode);
+        ui.separator();
+        ui.label(if replay.shack { "Quest" } else { "PC" });
+        if replay.competitive {
+            ui.separator();
+            ui.label("Competitive");
+        }
+        ui.separator();
+        ui.label("Elapsed:");
+        ui.label(elapsed_since(&replay.created_date).map(elapsed_text).unwrap_or_else(|| "unknown".to_string()));
+        ui.separator();
+        ui.label(format!("{} player{}", replay.users.len(), if replay.users.len() == 1 { "" } else { "s" }));
+    });
+
+    if !replay.users.is_empty() {
+        egui::ScrollArea::horizontal()
+            .max_height(72.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing = egui::vec2(8.0, 0.0);
+                    for (idx, user) in replay.users.iter().enumerate() {
+                        ui.push_id(idx, |ui| {
+                            app.render_user_avatar(ui, ctx, user);
+                        });
+                    }
+                });
+            });
+    }
+}
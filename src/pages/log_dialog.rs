@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use eframe::egui::{self, Context};
+use log::{Level, LevelFilter};
+
+use crate::app::ReplayApp;
+use crate::tools::listing::contains_ignore_case;
+use crate::tools::logging::{self, LogRecord};
+
+const LEVELS: [LevelFilter; 5] = [
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+pub fn render_log_dialog(app: &mut ReplayApp, ctx: &Context) {
+    if !app.log_viewer.open {
+        return;
+    }
+    // Records arrive from background threads without a repaint of their own.
+    ctx.request_repaint_after(Duration::from_secs(1));
+
+    let viewer = &mut app.log_viewer;
+    let records: Vec<LogRecord> = logging::recent_records()
+        .into_iter()
+        .rev()
+        .filter(|record| record.level <= viewer.max_level)
+        .filter(|record| contains_ignore_case(&record.target, &viewer.target))
+        .filter(|record| contains_ignore_case(&record.message, &viewer.filter))
+        .collect();
+
+    let mut open = viewer.open;
+    let mut open_folder = false;
+    egui::Window::new("Log")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([720.0, 420.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Level:");
+                egui::ComboBox::new(egui::Id::new("log_level"), "")
+                    .selected_text(viewer.max_level.as_str())
+                    .show_ui(ui, |ui| {
+                        for level in LEVELS {
+                            ui.selectable_value(&mut viewer.max_level, level, level.as_str());
+                        }
+                    });
+                ui.add(egui::TextEdit::singleline(&mut viewer.target)
+                    .hint_text("Module")
+                    .desired_width(140.0));
+                ui.add(egui::TextEdit::singleline(&mut viewer.filter)
+                    .hint_text("Filter")
+                    .desired_width(180.0));
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Clear").on_hover_text("Clear the shown records, the log file is kept").clicked() {
+                        logging::clear_recent_records();
+                    }
+                    if ui.button("Copy").on_hover_text("Copy the shown records").clicked() {
+                        let text: Vec<String> = records.iter().rev().map(LogRecord::line).collect();
+                        ui.ctx().copy_text(text.join("\n"));
+                    }
+                    if ui.button("Open Folder").clicked() {
+                        open_folder = true;
+                    }
+                });
+            });
+            if let Some(path) = logging::log_file_path() {
+                ui.weak(format!("Written to {}", path.display()));
+            }
+            ui.separator();
+
+            if records.is_empty() {
+                ui.label("No log records.");
+                return;
+            }
+            let row_height = ui.text_style_height(&egui::TextStyle::Monospace) + 2.0;
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show_rows(ui, row_height, records.len(), |ui, row_range| {
+                    for record in &records[row_range] {
+                        let color = match record.level {
+                            Level::Error => ui.style().visuals.error_fg_color,
+                            Level::Warn => ui.style().visuals.warn_fg_color,
+                            Level::Info => ui.style().visuals.text_color(),
+                            Level::Debug | Level::Trace => ui.style().visuals.weak_text_color(),
+                        };
+                        let text = egui::RichText::new(record.line()).monospace().color(color);
+                        if ui.add(egui::Label::new(text).truncate().sense(egui::Sense::click()))
+                            .on_hover_text("Click to copy")
+                            .clicked()
+                        {
+                            ui.ctx().copy_text(record.line());
+                        }
+                    }
+                });
+        });
+    viewer.open = open;
+
+    if open_folder {
+        if let Some(path) = logging::log_file_path() {
+            if let Err(e) = open::that(path.parent().unwrap_or(&path)) {
+                app.show_error(format!("Failed to open folder: {}", e));
+            }
+        }
+    }
+}
@@ -1,14 +1,32 @@
-use eframe::egui::{self, Context};
+use eframe::egui::{self, Context, Key, Modifiers};
 
-use crate::app::ReplayApp;
+use crate::app::{ReplayApp, SortKey};
 use crate::tools::replay_processor::ReplayItem;
+use crate::tools::watchlist::{add_to_watchlist, expiry_text, time_until_expiry};
+
+/// The first filter field, focused with `/`.
+const FILTER_FOCUS_ID: &str = "filter_game_mode";
 
 pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
     ui.horizontal(|ui| {
         ui.heading("Replay Downloader");
+        if app.replay_list.is_loading && !app.search.active {
+            ui.spinner();
+            ui.label("Loading replays...");
+        }
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             if app.styled_button(ui, "Refresh").clicked() {
-                app.refresh_replays();
+                if app.search.active {
+                    app.start_catalogue_search();
+                } else {
+                    app.refresh_replays();
+                }
+            }
+            if app.styled_button(ui, "Batch Download").clicked() {
+                app.batch.open = true;
+            }
+            if app.styled_button(ui, &format!("Watchlist ({})", app.settings.watchlist.len())).clicked() {
+                app.watchlist_open = true;
             }
         });
     });
@@ -25,9 +43,24 @@ pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
             // Game Mode filter
             ui.vertical(|ui| {
                 ui.label("Game Mode:");
-                ui.add_sized([field_width, field_height],
+                let response = ui.add_sized([field_width, field_height],
                     egui::TextEdit::singleline(&mut app.replay_list.filters.game_mode)
-                        .hint_text("Filter"));
+                        .id(egui::Id::new(FILTER_FOCUS_ID))
+                        .hint_text("e.g. SND"));
+                // The game mode is filtered by the server, so the listing is fetched again once
+                // the edit is finished rather than on every key.
+                let edited = egui::Id::new("filter_game_mode_edited");
+                if response.changed() {
+                    ui.data_mut(|data| data.insert_temp(edited, true));
+                }
+                if response.lost_focus() && ui.data_mut(|data| data.remove_temp::<bool>(edited)).unwrap_or(false) {
+                    if app.search.active {
+                        app.start_catalogue_search();
+                    } else {
+                        app.replay_list.current_page = 0;
+                        app.refresh_replays();
+                    }
+                }
             });
 
             // Map filter
@@ -51,7 +84,7 @@ pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
                 ui.label("User ID:");
                 ui.add_sized([field_width, field_height],
                     egui::TextEdit::singleline(&mut app.replay_list.filters.user_id)
-                        .hint_text("Filter"));
+                        .hint_text("ID or nickname"));
             });
 
             // Platform filter
@@ -73,25 +106,47 @@ pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
                     });
                 
                 if app.replay_list.filters.platform != old_platform {
-                    app.replay_list.current_page = 0;
-                    app.refresh_replays();
+                    // The platform is filtered by the server, so the listing has to be fetched again.
+                    if app.search.active {
+                        app.start_catalogue_search();
+                    } else {
+                        app.replay_list.current_page = 0;
+                        app.refresh_replays();
+                    }
                 }
             });
         });
+
+        render_sort_controls(app, ui);
+        render_search_bar(app, ui);
     });
 
     let filtered_replays = app.get_filtered_replays();
+    render_selection_bar(app, ui, &filtered_replays);
+    handle_keys(app, ctx, &filtered_replays);
 
     let replay_item_height = 200.0;
     let horizontal_margin = 8.0;
     let full_width = ui.available_width();
 
-    egui::ScrollArea::vertical()
-        .auto_shrink([false; 2])
+    let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+    if let Some(offset) = app.replay_cursor.scroll_offset(replay_item_height + ui.spacing().item_spacing.y) {
+        scroll_area = scroll_area.vertical_scroll_offset(offset);
+    }
+    scroll_area
         .show_rows(ui, replay_item_height, filtered_replays.len(), |ui, row_range| {
+            app.replay_cursor.shown = row_range.clone();
             if filtered_replays.is_empty() {
+                if app.replay_list.is_loading && !app.search.active {
+                    render_loading_skeleton(ui, full_width - 2.0 * horizontal_margin, replay_item_height);
+                    return;
+                }
                 ui.centered_and_justified(|ui| {
-                    ui.label("No replays found...");
+                    if app.search.running {
+                        ui.label("Searching...");
+                    } else {
+                        ui.label("No replays found...");
+                    }
                 });
             } else {
                 for row in row_range {
@@ -101,12 +156,17 @@ pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
                         egui::Sense::hover(),
                     );
                     let rect = rect.translate(egui::vec2(horizontal_margin, 0.0));
+                    let highlighted = app.replay_cursor.index == Some(row);
+                    if highlighted && app.replay_cursor.reveal {
+                        ui.scroll_to_rect(rect, None);
+                        app.replay_cursor.reveal = false;
+                    }
                     ui.allocate_new_ui(
                         egui::UiBuilder::new()
                             .max_rect(rect)  
                             .layout(egui::Layout::top_down(egui::Align::Center)),
                         |ui| {
-                            render_replay_item_with_width(app, ui, ctx, replay, rect.width());
+                            render_replay_item_with_width(app, ui, ctx, replay, rect.width(), highlighted);
                         },
                     );
                     ui.add_space(4.0);
@@ -114,7 +174,7 @@ pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
             }
         });
 
-    if app.replay_list.total_pages > 0 {
+    if app.replay_list.total_pages > 0 && !app.search.active {
         egui::Area::new(egui::Id::new("pagination_controls"))
             .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-20.0, -20.0))
             .order(egui::Order::Foreground)
@@ -136,11 +196,7 @@ pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
                                 egui::Button::new("<")
                                     .min_size(egui::vec2(32.0, 32.0))
                             ).clicked() {
-                                app.replay_list.current_page -= 1;
-                                app.refresh_replays();
-                                ctx.memory_mut(|mem| {
-                                    mem.data.clear();
-                                });
+                                turn_page(app, ctx, -1);
                             }
                             
                             ui.label(format!("Page {} of {}",
@@ -153,11 +209,7 @@ pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
                                 egui::Button::new(">")
                                     .min_size(egui::vec2(32.0, 32.0))
                             ).clicked() {
-                                app.replay_list.current_page += 1;
-                                app.refresh_replays();
-                                ctx.memory_mut(|mem| {
-                                    mem.data.clear();
-                                });
+                                turn_page(app, ctx, 1);
                             }
                         });
                     });
@@ -165,18 +217,246 @@ pub fn render_main_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
     }
 }
 
+/// Moves to the previous or next listing page.
+fn turn_page(app: &mut ReplayApp, ctx: &Context, delta: isize) {
+    let last_page = app.replay_list.total_pages.saturating_sub(1);
+    let page = app.replay_list.current_page.saturating_add_signed(delta).min(last_page);
+    if page == app.replay_list.current_page {
+        return;
+    }
+    app.replay_list.current_page = page;
+    app.replay_cursor.index = None;
+    app.refresh_replays();
+    ctx.memory_mut(|mem| {
+        mem.data.clear();
+    });
+}
+
+/// Keyboard control of the replay cards, see the shortcut overview.
+fn handle_keys(app: &mut ReplayApp, ctx: &Context, visible: &[ReplayItem]) {
+    if ctx.wants_keyboard_input() || app.shortcuts_open {
+        return;
+    }
+
+    if ctx.input_mut(|input| input.consume_key(Modifiers::NONE, Key::Slash)) {
+        // Drop the typed character too, or it ends up in the field focused here.
+        ctx.input_mut(|input| input.events.retain(|event| !matches!(event, egui::Event::Text(text) if text == "/")));
+        ctx.memory_mut(|mem| mem.request_focus(egui::Id::new(FILTER_FOCUS_ID)));
+        return;
+    }
+
+    // Enter and Space still press a focused button.
+    let button_focused = ctx.memory(|mem| mem.focused().is_some());
+    let (down, up, previous, next, enter, space) = ctx.input_mut(|input| (
+        input.consume_key(Modifiers::NONE, Key::ArrowDown) || input.consume_key(Modifiers::NONE, Key::J),
+        input.consume_key(Modifiers::NONE, Key::ArrowUp) || input.consume_key(Modifiers::NONE, Key::K),
+        input.consume_key(Modifiers::NONE, Key::ArrowLeft),
+        input.consume_key(Modifiers::NONE, Key::ArrowRight),
+        !button_focused && input.consume_key(Modifiers::NONE, Key::Enter),
+        !button_focused && input.consume_key(Modifiers::NONE, Key::Space),
+    ));
+
+    if down {
+        app.replay_cursor.step(1, visible.len());
+    }
+    if up {
+        app.replay_cursor.step(-1, visible.len());
+    }
+    if (previous || next) && !app.search.active && app.replay_list.total_pages > 0 {
+        turn_page(app, ctx, if next { 1 } else { -1 });
+    }
+
+    let Some(replay) = app.replay_cursor.get(visible.len()).map(|index| &visible[index]) else {
+        return;
+    };
+    if space && !app.replay_list.selected.remove(&replay.id) {
+        app.replay_list.selected.insert(replay.id.clone());
+    }
+    if enter {
+        if app.downloaded_replays.contains(&replay.id) {
+            app.show_info("Replay already downloaded");
+        } else if app.downloading_replay_id.as_ref() != Some(&replay.id) {
+            app.process_online_replay(&replay.id);
+        }
+    }
+}
+
+/// Select all/none and the actions applied to every ticked replay.
+fn render_selection_bar(app: &mut ReplayApp, ui: &mut egui::Ui, visible: &[ReplayItem]) {
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!visible.is_empty(), egui::Button::new("Select all")).clicked() {
+            app.replay_list.selected.extend(visible.iter().map(|replay| replay.id.clone()));
+        }
+        if ui.add_enabled(!app.replay_list.selected.is_empty(), egui::Button::new("Select none")).clicked() {
+            app.replay_list.selected.clear();
+        }
+
+        let selected = app.selected_replays();
+        if selected.is_empty() {
+            return;
+        }
+        ui.separator();
+        ui.label(format!("{} selected", selected.len()));
+        let ids: Vec<String> = selected.iter().map(|replay| replay.id.clone()).collect();
+
+        if ui.button("Download").clicked() {
+            let pending: Vec<String> = ids.iter()
+                .filter(|id| !app.downloaded_replays.contains(*id))
+                .cloned()
+                .collect();
+            if pending.is_empty() {
+                app.show_info("All selected replays are already downloaded");
+            } else {
+                app.queue_downloads(&pending);
+            }
+        }
+        if ui.button("Copy IDs").clicked() {
+            ui.ctx().copy_text(ids.join("\n"));
+            app.show_success(format!("Copied {} replay IDs", ids.len()));
+        }
+        if ui.button("Export JSON").clicked() {
+            export_selection(app, &selected);
+        }
+        if ui.button("Add to watchlist").clicked() {
+            let added = add_to_watchlist(&mut app.settings.watchlist, &selected);
+            if let Err(err) = app.save_settings() {
+                app.show_error(format!("Error saving settings: {}", err));
+            } else {
+                app.show_success(format!("Added {} replays to the watchlist", added));
+            }
+        }
+    });
+}
+
+fn export_selection(app: &mut ReplayApp, selected: &[ReplayItem]) {
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter("JSON", &["json"])
+        .set_file_name("replays.json")
+        .save_file()
+    else {
+        return;
+    };
+
+    let result = serde_json::to_string_pretty(selected)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => app.show_success(format!("Exported {} replays to {}", selected.len(), path.display())),
+        Err(e) => app.show_error(format!("Failed to export replays: {}", e)),
+    }
+}
+
+fn render_sort_controls(app: &mut ReplayApp, ui: &mut egui::Ui) {
+    let old_sort = app.settings.replay_sort;
+
+    ui.horizontal(|ui| {
+        ui.label("Sort by:");
+        egui::ComboBox::new(egui::Id::new("replay_sort"), "")
+            .selected_text(app.settings.replay_sort.key.label())
+            .show_ui(ui, |ui| {
+                for key in SortKey::ALL {
+                    ui.selectable_value(&mut app.settings.replay_sort.key, key, key.label());
+                }
+            });
+        ui.add_enabled_ui(app.settings.replay_sort.key != SortKey::Server, |ui| {
+            let descending = &mut app.settings.replay_sort.descending;
+            if ui.button(if *descending { "Descending" } else { "Ascending" }).clicked() {
+                *descending = !*descending;
+            }
+        });
+    });
+
+    if app.settings.replay_sort != old_sort {
+        let sort = app.settings.replay_sort;
+        if let Err(err) = ReplayApp::update_saved_settings(|saved| saved.replay_sort = sort) {
+            app.show_error(format!("Error saving settings: {}", err));
+        }
+    }
+}
+
+/// Offers to search the whole catalogue when filters only apply to the current page, and
+/// shows the progress of a running search.
+fn render_search_bar(app: &mut ReplayApp, ui: &mut egui::Ui) {
+    if app.search.active {
+        ui.horizontal(|ui| {
+            if app.search.running {
+                ui.spinner();
+            }
+            ui.add(
+                egui::ProgressBar::new(app.search.progress())
+                    .desired_width(200.0)
+                    .text(format!("{} of {} scanned", app.search.scanned, app.search.total)),
+            );
+            ui.label(format!("{} matches", app.get_filtered_replays().len()));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("Back to pages").clicked() {
+                    app.clear_catalogue_search();
+                }
+                if app.search.running {
+                    if ui.button("Stop").clicked() {
+                        app.stop_catalogue_search();
+                    }
+                } else if ui.button("Search again").clicked() {
+                    app.start_catalogue_search();
+                }
+            });
+        });
+    } else if app.replay_list.filters.has_local_filters() {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Filters apply to page {} only.",
+                app.replay_list.current_page + 1
+            ));
+            if ui.button("Search all pages").clicked() {
+                app.start_catalogue_search();
+            }
+        });
+    }
+}
+
+/// Placeholder cards shown while the first page is loading.
+fn render_loading_skeleton(ui: &mut egui::Ui, width: f32, height: f32) {
+    let pulse = ((ui.input(|i| i.time) * 3.0).sin() * 0.5 + 0.5) as f32;
+    let base = ui.style().visuals.extreme_bg_color;
+    let bar = ui.style().visuals.widgets.inactive.bg_fill.gamma_multiply(0.5 + 0.5 * pulse);
+
+    for _ in 0..3 {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::hover());
+        let rect = rect.translate(egui::vec2(8.0, 0.0));
+        let painter = ui.painter();
+        painter.rect_filled(rect, 4.0, base);
+
+        let left = rect.left() + 16.0;
+        let top = rect.top() + 16.0;
+        painter.rect_filled(egui::Rect::from_min_size(egui::pos2(left, top), egui::vec2(220.0, 32.0)), 8.0, bar);
+        painter.rect_filled(egui::Rect::from_min_size(egui::pos2(left, top + 48.0), egui::vec2(320.0, 14.0)), 4.0, bar);
+        painter.rect_filled(egui::Rect::from_min_size(egui::pos2(left, top + 72.0), egui::vec2(160.0, 14.0)), 4.0, bar);
+        for i in 0..5 {
+            let avatar = egui::Rect::from_min_size(egui::pos2(left + i as f32 * 72.0, rect.bottom() - 80.0), egui::vec2(64.0, 64.0));
+            painter.rect_filled(avatar, 8.0, bar);
+        }
+        ui.add_space(4.0);
+    }
+}
+
 fn render_replay_item_with_width(
     app: &mut ReplayApp,
     ui: &mut egui::Ui,
     ctx: &Context,
     replay: &ReplayItem,
     width: f32,
+    highlighted: bool,
 ) {
     ui.push_id(replay.id.as_str(), |ui| {
         egui::Frame::new()
             .outer_margin(egui::Margin::same(0)) 
             .show(ui, |ui| {
-                egui::Frame::group(ui.style())
+                let mut frame = egui::Frame::group(ui.style());
+                if highlighted {
+                    frame = frame.stroke(ui.style().visuals.selection.stroke);
+                }
+                frame
                     .fill(ui.style().visuals.extreme_bg_color)
                     .inner_margin(egui::Margin::symmetric(8, 0)) 
                     .show(ui, |ui| {
@@ -196,6 +476,15 @@ fn render_replay_item_contents(
     ui.vertical(|ui| {
         ui.add_space(8.0);
         ui.horizontal(|ui| {
+            let mut selected = app.replay_list.selected.contains(&replay.id);
+            if ui.checkbox(&mut selected, "").changed() {
+                if selected {
+                    app.replay_list.selected.insert(replay.id.clone());
+                } else {
+                    app.replay_list.selected.remove(&replay.id);
+                }
+            }
+
             egui::Frame::new()
                 .fill(ui.style().visuals.extreme_bg_color)
                 .stroke(egui::Stroke::new(1.0, ui.style().visuals.window_stroke().color))
@@ -213,7 +502,7 @@ fn render_replay_item_contents(
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 let is_downloading = app.downloading_replay_id
                     .as_ref()
-                    .map_or(false, |id| id == &replay.id);
+                    .is_some_and(|id| id == &replay.id);
                 
                 let is_downloaded = app.downloaded_replays.contains(&replay.id);
 
@@ -236,6 +525,14 @@ fn render_replay_item_contents(
                             }
                         });
                 }
+
+                egui::Frame::new()
+                    .inner_margin(egui::Margin { top: 8, left: 0, right: 0, bottom: 0 })
+                    .show(ui, |ui| {
+                        if ui.add(egui::Button::new("Details").min_size(egui::vec2(80.0, 32.0))).clicked() {
+                            app.open_replay_details(replay);
+                        }
+                    });
             });
         });
 
@@ -390,6 +687,20 @@ fn render_replay_item_contents(
             ui.spacing_mut().item_spacing.x = 4.0;
             ui.label("Time Since:");
             ui.label(format!("{}s", replay.time_since));
+
+            if let Some(remaining) = time_until_expiry(&replay.expires) {
+                ui.separator();
+                let window = chrono::TimeDelta::hours(app.settings.watchlist_expiry_window_hours as i64);
+                let countdown = expiry_text(remaining);
+                if remaining <= window {
+                    ui.colored_label(ui.style().visuals.warn_fg_color, countdown);
+                } else {
+                    ui.label(countdown);
+                }
+                if app.settings.watchlist.iter().any(|entry| entry.id == replay.id) {
+                    ui.weak("(watched)");
+                }
+            }
         });
 
         ui.separator();
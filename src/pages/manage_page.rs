@@ -1,6 +1,11 @@
 use std::fs;
-use eframe::egui::{self, Context};
+use std::path::{Path, PathBuf};
+use eframe::egui::{self, Context, Key, Modifiers};
 use crate::app::{ReplayApp, Page};
+use crate::tools::library::downloaded_replays;
+
+/// Shown for files whose replay ID is not known.
+pub const UNKNOWN_REPLAY_ID: &str = "Unknown";
 
 #[derive(Clone, Debug)]
 pub struct DownloadedReplayInfo {
@@ -16,163 +21,132 @@ pub struct DownloadedReplayInfo {
 
 // TODO: Store replay metadata on download so we can display it later
 
-impl ReplayApp {
-    pub fn scan_downloaded_replays(&self) -> Vec<DownloadedReplayInfo> {
-        let mut replays = Vec::new();
+/// Reads the replays in `download_dir` and `depth` levels of subdirectories. This walks the
+/// directory tree, so the app runs it on a background thread and keeps the result until
+/// something changes.
+pub fn scan_downloaded_replays(download_dir: &Path, depth: usize) -> Vec<DownloadedReplayInfo> {
+    let mut replays = Vec::new();
+    // Library index paths are canonical.
+    let root = fs::canonicalize(download_dir).unwrap_or_else(|_| download_dir.to_path_buf());
+    
+    for (full_path, replay_id) in downloaded_replays(download_dir, depth) {
+        let Some(filename_str) = full_path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let display_name = full_path
+            .strip_prefix(download_dir)
+            .or_else(|_| full_path.strip_prefix(&root))
+            .unwrap_or(&full_path)
+            .display()
+            .to_string();
+
+        // Get file metadata
+        let metadata = fs::metadata(&full_path).ok();
+        let file_size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+        let modified_time = metadata.and_then(|m| m.modified().ok());
         
-        if let Ok(entries) = fs::read_dir(&self.settings.download_dir) {
-            for entry in entries.flatten() {
-                if let Ok(file_type) = entry.file_type() {
-                    if file_type.is_file() {
-                        if let Some(ext) = entry.path().extension() {
-                            if ext == "replay" {
-                                if let Some(filename) = entry.path().file_name() {
-                                    if let Some(filename_str) = filename.to_str() {
-                                        let full_path = entry.path();
-                                        
-                                        // Get file metadata
-                                        let metadata = fs::metadata(&full_path).ok();
-                                        let file_size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
-                                        let modified_time = metadata.and_then(|m| m.modified().ok());
-                                        
-                                        // Try to extract replay ID from filename
-                                        let replay_id = if let Some(id_start) = filename_str.rfind('(') {
-                                            if let Some(id_end) = filename_str[id_start..].find(')') {
-                                                filename_str[id_start + 1..id_start + id_end].to_string()
-                                            } else {
-                                                "Unknown".to_string()
-                                            }
-                                        } else {
-                                            // Fallback: try to read from file content
-                                            if let Ok(mut file) = fs::File::open(&full_path) {
-                                                use std::io::Read;
-                                                let mut buffer = [0; 1024];
-                                                if file.read(&mut buffer).is_ok() {
-                                                    let content = String::from_utf8_lossy(&buffer);
-                                                    if let Some(id_start) = content.find("\"id\":\"") {
-                                                        let id_start = id_start + 6;
-                                                        if let Some(id_end) = content[id_start..].find('"') {
-                                                            content[id_start..id_start + id_end].to_string()
-                                                        } else {
-                                                            "Unknown".to_string()
-                                                        }
-                                                    } else {
-                                                        "Unknown".to_string()
-                                                    }
-                                                } else {
-                                                    "Unknown".to_string()
-                                                }
-                                            } else {
-                                                "Unknown".to_string()
-                                            }
-                                        };
-                                        
-                                        
-                                        let all_parts: Vec<&str> = filename_str.split('-').collect();
-                                        let (game_mode, map_name, date) = if all_parts.len() >= 3 {
-                                            let common_modes = ["SND", "TDM", "DM", "KOTH", "TTT", "ZWV", "PUSH", "TANKTDM"];
-                                            
-                                            let mut mode_index = None;
-                                            for (i, part) in all_parts.iter().enumerate() {
-                                                if common_modes.iter().any(|&mode| part.to_uppercase() == mode) {
-                                                    mode_index = Some(i);
-                                                    break;
-                                                }
-                                            }
-                                            
-                                            if let Some(mode_idx) = mode_index {
-                                                let map_name = if mode_idx > 0 {
-                                                    Some(all_parts[0..mode_idx].join("-"))
-                                                } else {
-                                                    None
-                                                };
-                                                
-                                                let game_mode = Some(all_parts[mode_idx].to_string());
-                                                
-                                                let mut date = None;
-                                                for part in &all_parts[mode_idx + 1..] {
-                                                    if part.len() >= 10 && part.chars().nth(4) == Some('.') && part.chars().nth(7) == Some('.') {
-                                                        let date_clean = part.split('(').next().unwrap_or("");
-                                                        if !date_clean.is_empty() {
-                                                            date = Some(date_clean.replace('.', "/"));
-                                                        }
-                                                        break;
-                                                    }
-                                                }
-                                                
-                                                (game_mode, map_name, date)
-                                            } else {
-                                                let mut date_index = None;
-                                                for (i, part) in all_parts.iter().enumerate() {
-                                                    if part.len() >= 10 && part.chars().nth(4) == Some('.') && part.chars().nth(7) == Some('.') {
-                                                        date_index = Some(i);
-                                                        break;
-                                                    }
-                                                }
-                                                
-                                                if let Some(date_idx) = date_index {
-                                                    if date_idx > 0 {
-                                                        let potential_mode = all_parts[date_idx - 1];
-                                                        if common_modes.iter().any(|&mode| potential_mode.to_uppercase() == mode) {
-                                                            let map_name = if date_idx > 1 {
-                                                                Some(all_parts[0..date_idx - 1].join("-"))
-                                                            } else {
-                                                                None
-                                                            };
-                                                            let game_mode = Some(potential_mode.to_string());
-                                                            let date_clean = all_parts[date_idx].split('(').next().unwrap_or("");
-                                                            let date = if !date_clean.is_empty() {
-                                                                Some(date_clean.replace('.', "/"))
-                                                            } else {
-                                                                None
-                                                            };
-                                                            (game_mode, map_name, date)
-                                                        } else {
-                                                            (None, None, None)
-                                                        }
-                                                    } else {
-                                                        (None, None, None)
-                                                    }
-                                                } else {
-                                                    (None, None, None)
-                                                }
-                                            }
-                                        } else {
-                                            (None, None, None)
-                                        };
-                                        
-                                        replays.push(DownloadedReplayInfo {
-                                            id: replay_id,
-                                            filename: filename_str.to_string(),
-                                            full_path,
-                                            file_size,
-                                            modified_time,
-                                            game_mode,
-                                            map_name,
-                                            date,
-                                        });
-                                    }
-                                }
-                            }
+        let replay_id = replay_id.unwrap_or_else(|| UNKNOWN_REPLAY_ID.to_string());
+        
+        let all_parts: Vec<&str> = filename_str.split('-').collect();
+        let (game_mode, map_name, date) = if all_parts.len() >= 3 {
+            let common_modes = ["SND", "TDM", "DM", "KOTH", "TTT", "ZWV", "PUSH", "TANKTDM"];
+            
+            let mut mode_index = None;
+            for (i, part) in all_parts.iter().enumerate() {
+                if common_modes.iter().any(|&mode| part.to_uppercase() == mode) {
+                    mode_index = Some(i);
+                    break;
+                }
+            }
+            
+            if let Some(mode_idx) = mode_index {
+                let map_name = if mode_idx > 0 {
+                    Some(all_parts[0..mode_idx].join("-"))
+                } else {
+                    None
+                };
+                
+                let game_mode = Some(all_parts[mode_idx].to_string());
+                
+                let mut date = None;
+                for part in &all_parts[mode_idx + 1..] {
+                    if part.len() >= 10 && part.chars().nth(4) == Some('.') && part.chars().nth(7) == Some('.') {
+                        let date_clean = part.split('(').next().unwrap_or("");
+                        if !date_clean.is_empty() {
+                            date = Some(date_clean.replace('.', "/"));
+                        }
+                        break;
+                    }
+                }
+                
+                (game_mode, map_name, date)
+            } else {
+                let mut date_index = None;
+                for (i, part) in all_parts.iter().enumerate() {
+                    if part.len() >= 10 && part.chars().nth(4) == Some('.') && part.chars().nth(7) == Some('.') {
+                        date_index = Some(i);
+                        break;
+                    }
+                }
+                
+                if let Some(date_idx) = date_index {
+                    if date_idx > 0 {
+                        let potential_mode = all_parts[date_idx - 1];
+                        if common_modes.iter().any(|&mode| potential_mode.to_uppercase() == mode) {
+                            let map_name = if date_idx > 1 {
+                                Some(all_parts[0..date_idx - 1].join("-"))
+                            } else {
+                                None
+                            };
+                            let game_mode = Some(potential_mode.to_string());
+                            let date_clean = all_parts[date_idx].split('(').next().unwrap_or("");
+                            let date = if !date_clean.is_empty() {
+                                Some(date_clean.replace('.', "/"))
+                            } else {
+                                None
+                            };
+                            (game_mode, map_name, date)
+                        } else {
+                            (None, None, None)
                         }
+                    } else {
+                        (None, None, None)
                     }
+                } else {
+                    (None, None, None)
                 }
             }
-        }
+        } else {
+            (None, None, None)
+        };
         
-        // Sort by modified time (newest first)
-        replays.sort_by(|a, b| {
-            b.modified_time.cmp(&a.modified_time)
+        replays.push(DownloadedReplayInfo {
+            id: replay_id,
+            filename: display_name,
+            full_path,
+            file_size,
+            modified_time,
+            game_mode,
+            map_name,
+            date,
         });
-        
-        replays
     }
     
+    // Sort by modified time (newest first)
+    replays.sort_by(|a, b| {
+        b.modified_time.cmp(&a.modified_time)
+    });
+    
+    replays
+}
+
+impl ReplayApp {
     pub fn delete_replay_file(&mut self, replay_info: &DownloadedReplayInfo) -> Result<(), std::io::Error> {
         fs::remove_file(&replay_info.full_path)?;
         
         // Remove from downloaded_replays set
         self.downloaded_replays.remove(&replay_info.id);
+        self.rescan_library();
         
         // Show success notification
         self.show_success(format!("Deleted replay: {}", replay_info.filename));
@@ -206,6 +180,87 @@ fn delete_all_replays(app: &mut ReplayApp, downloaded_replays: &[DownloadedRepla
     }
 }
 
+/// Memory key of the replay waiting for a confirmed delete.
+const CONFIRM_DELETE_ID: &str = "confirm_delete_replay";
+
+/// Keyboard control of the replay rows, see the shortcut overview.
+fn handle_keys(app: &mut ReplayApp, ctx: &Context, downloaded_replays: &[DownloadedReplayInfo]) {
+    let dialog_open = ctx.memory(|mem| {
+        mem.data.get_temp::<bool>(egui::Id::new("show_delete_all_dialog")).unwrap_or(false)
+            || mem.data.get_temp::<PathBuf>(egui::Id::new(CONFIRM_DELETE_ID)).is_some()
+    });
+    if ctx.wants_keyboard_input() || app.shortcuts_open || dialog_open {
+        return;
+    }
+
+    let (down, up, delete) = ctx.input_mut(|input| (
+        input.consume_key(Modifiers::NONE, Key::ArrowDown) || input.consume_key(Modifiers::NONE, Key::J),
+        input.consume_key(Modifiers::NONE, Key::ArrowUp) || input.consume_key(Modifiers::NONE, Key::K),
+        input.consume_key(Modifiers::NONE, Key::Delete),
+    ));
+    if down {
+        app.manage_cursor.step(1, downloaded_replays.len());
+    }
+    if up {
+        app.manage_cursor.step(-1, downloaded_replays.len());
+    }
+    if delete {
+        if let Some(index) = app.manage_cursor.get(downloaded_replays.len()) {
+            let path = downloaded_replays[index].full_path.clone();
+            ctx.memory_mut(|mem| mem.data.insert_temp(egui::Id::new(CONFIRM_DELETE_ID), path));
+        }
+    }
+}
+
+/// Asks before deleting the replay picked with the Delete key.
+fn render_delete_confirmation(app: &mut ReplayApp, ctx: &Context, downloaded_replays: &[DownloadedReplayInfo]) {
+    let Some(path) = ctx.memory(|mem| mem.data.get_temp::<PathBuf>(egui::Id::new(CONFIRM_DELETE_ID))) else {
+        return;
+    };
+    let Some(replay) = downloaded_replays.iter().find(|replay| replay.full_path == path) else {
+        ctx.memory_mut(|mem| mem.data.remove::<PathBuf>(egui::Id::new(CONFIRM_DELETE_ID)));
+        return;
+    };
+
+    let (mut confirm, mut cancel) = ctx.input_mut(|input| (
+        input.consume_key(Modifiers::NONE, Key::Enter),
+        input.consume_key(Modifiers::NONE, Key::Escape),
+    ));
+    egui::Window::new("Confirm Delete")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(10.0);
+                ui.label(format!("Delete {}?", replay.filename));
+                ui.label(egui::RichText::new("This action cannot be undone!").color(egui::Color32::from_rgb(255, 100, 100)));
+                ui.add_space(16.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel (Esc)").clicked() {
+                        cancel = true;
+                    }
+                    ui.add_space(20.0);
+                    ui.visuals_mut().widgets.inactive.bg_fill = egui::Color32::from_rgb(180, 40, 40);
+                    ui.visuals_mut().widgets.hovered.bg_fill = egui::Color32::from_rgb(200, 50, 50);
+                    if ui.button("Delete (Enter)").clicked() {
+                        confirm = true;
+                    }
+                });
+                ui.add_space(10.0);
+            });
+        });
+
+    if confirm {
+        if let Err(e) = app.delete_replay_file(replay) {
+            app.show_error(format!("Failed to delete replay: {}", e));
+        }
+    }
+    if confirm || cancel {
+        ctx.memory_mut(|mem| mem.data.remove::<PathBuf>(egui::Id::new(CONFIRM_DELETE_ID)));
+    }
+}
+
 pub fn render_manage_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
     ui.heading("Manage Downloaded Replays");
     ui.add_space(8.0);
@@ -216,9 +271,16 @@ pub fn render_manage_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context)
     });
     ui.add_space(12.0);
     
-    // Scan for downloaded replays
-    let downloaded_replays = app.scan_downloaded_replays();
+    let downloaded_replays = app.downloaded_files.clone();
     
+    if downloaded_replays.is_empty() && app.library_scanning() {
+        ui.vertical_centered(|ui| {
+            ui.add_space(50.0);
+            ui.spinner();
+            ui.label("Scanning the download directory...");
+        });
+        return;
+    }
     if downloaded_replays.is_empty() {
         ui.vertical_centered(|ui| {
             ui.add_space(50.0);
@@ -233,6 +295,9 @@ pub fn render_manage_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context)
         return;
     }
     
+    handle_keys(app, ctx, &downloaded_replays);
+    render_delete_confirmation(app, ctx, &downloaded_replays);
+
     // Display total count and total size
     let total_size: u64 = downloaded_replays.iter().map(|r| r.file_size).sum();
     let total_size_mb = total_size as f64 / (1024.0 * 1024.0);
@@ -255,6 +320,7 @@ pub fn render_manage_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context)
         ui.separator();
         
         if ui.button("Refresh List").clicked() {
+            app.rescan_library();
             app.show_info("Replay list refreshed");
         }
         
@@ -340,9 +406,13 @@ pub fn render_manage_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context)
     let row_spacing = 2.0;
     let replay_item_height = frame_vertical_margin + content_height + row_spacing;
 
-    egui::ScrollArea::vertical()
-        .auto_shrink([false; 2])
+    let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+    if let Some(offset) = app.manage_cursor.scroll_offset(replay_item_height + ui.spacing().item_spacing.y) {
+        scroll_area = scroll_area.vertical_scroll_offset(offset);
+    }
+    scroll_area
         .show_rows(ui, replay_item_height, downloaded_replays.len(), |ui, row_range| {
+            app.manage_cursor.shown = row_range.clone();
             let mut to_delete: Option<usize> = None;
             let mut show_info_for: Option<usize> = None;
             
@@ -353,6 +423,10 @@ pub fn render_manage_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context)
                     egui::Sense::hover(),
                 );
                 let rect = rect.translate(egui::vec2(horizontal_margin, 0.0));
+                if app.manage_cursor.index == Some(row) && app.manage_cursor.reveal {
+                    ui.scroll_to_rect(rect, None);
+                    app.manage_cursor.reveal = false;
+                }
                 ui.allocate_new_ui(
                     egui::UiBuilder::new()
                         .max_rect(rect)
@@ -391,6 +465,7 @@ pub fn render_manage_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context)
         });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_replay_row(
     app: &mut ReplayApp,
     ui: &mut egui::Ui,
@@ -403,7 +478,13 @@ fn render_replay_row(
 ) {
     ui.push_id(format!("replay_row_{}", index), |ui| {
         egui::Frame::new()
-            .fill(if index % 2 == 0 { ui.style().visuals.faint_bg_color } else { egui::Color32::TRANSPARENT })
+            .fill(if app.manage_cursor.index == Some(index) {
+                ui.style().visuals.selection.bg_fill.gamma_multiply(0.4)
+            } else if index.is_multiple_of(2) {
+                ui.style().visuals.faint_bg_color
+            } else {
+                egui::Color32::TRANSPARENT
+            })
             .inner_margin(egui::Margin::symmetric(12, 8))
             .show(ui, |ui| {
                 ui.set_width(width - 24.0);
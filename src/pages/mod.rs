@@ -1,9 +1,27 @@
 mod main_page;
+mod live_page;
+mod players_page;
 mod process_page;
 mod settings_page;
 mod manage_page;
+mod batch_dialog;
+mod watchlist_dialog;
+mod contact_dialog;
+mod details_dialog;
+mod history_dialog;
+mod log_dialog;
+mod shortcuts_dialog;
 
 pub use main_page::render_main_page;
+pub use live_page::render_live_page;
+pub use players_page::render_players_page;
 pub use process_page::render_process_page;
 pub use settings_page::render_settings_page;
-pub use manage_page::render_manage_page;
\ No newline at end of file
+pub use manage_page::{render_manage_page, scan_downloaded_replays, DownloadedReplayInfo, UNKNOWN_REPLAY_ID};
+pub use batch_dialog::render_batch_dialog;
+pub use watchlist_dialog::render_watchlist_dialog;
+pub use contact_dialog::render_contact_dialog;
+pub use details_dialog::render_details_dialog;
+pub use history_dialog::render_history_dialog;
+pub use log_dialog::render_log_dialog;
+pub use shortcuts_dialog::{handle_shortcuts, render_shortcuts_dialog};
\ No newline at end of file
@@ -0,0 +1,175 @@
+use eframe::egui::{self, Context};
+
+use crate::app::{Page, ReplayApp};
+use crate::tools::players::{PlayerSort, PlayerStats};
+use crate::tools::tracking::is_tracked;
+
+pub fn render_players_page(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context) {
+    app.update_players();
+
+    ui.horizontal(|ui| {
+        ui.heading("Players");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if app.styled_button(ui, "Reload").clicked() {
+                app.reload_player_library();
+            }
+            ui.weak(format!(
+                "{} players from {} fetched and {} downloaded replays",
+                app.player_browser.players.len(),
+                app.seen_replays.len(),
+                app.player_browser.library.existing().count()
+            ));
+        });
+    });
+    ui.separator();
+
+    ui.horizontal(|ui| {
+        ui.label("Search:");
+        ui.add(egui::TextEdit::singleline(&mut app.player_browser.filter)
+            .hint_text("ID, nickname, team or notes")
+            .desired_width(240.0));
+        ui.label("Sort by:");
+        let old_sort = app.player_browser.sort;
+        egui::ComboBox::new(egui::Id::new("player_sort"), "")
+            .selected_text(app.player_browser.sort.label())
+            .show_ui(ui, |ui| {
+                for sort in PlayerSort::ALL {
+                    ui.selectable_value(&mut app.player_browser.sort, sort, sort.label());
+                }
+            });
+        if app.player_browser.sort != old_sort {
+            app.player_browser.stale = true;
+        }
+    });
+    ui.add_space(4.0);
+
+    let visible: Vec<usize> = app.player_browser.players.iter()
+        .enumerate()
+        .filter(|(_, player)| {
+            app.player_browser.filter.is_empty()
+                || app.settings.address_book.user_matches(&player.user_id, &app.player_browser.filter)
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    ui.columns(2, |columns| {
+        let row_height = 24.0;
+        egui::ScrollArea::vertical()
+            .id_salt("player_list")
+            .auto_shrink([false; 2])
+            .show_rows(&mut columns[0], row_height, visible.len(), |ui, row_range| {
+                if visible.is_empty() {
+                    ui.label("No players yet. Players appear here once replays are loaded or downloaded.");
+                    return;
+                }
+                for row in row_range {
+                    let player = &app.player_browser.players[visible[row]];
+                    let selected = app.player_browser.selected.as_deref() == Some(player.user_id.as_str());
+                    let label = format!(
+                        "{}  ({} match{})",
+                        app.settings.address_book.display_name(&player.user_id),
+                        player.matches(),
+                        if player.matches() == 1 { "" } else { "es" }
+                    );
+                    if ui.add_sized([ui.available_width(), row_height], egui::SelectableLabel::new(selected, label)).clicked() {
+                        app.player_browser.selected = Some(player.user_id.clone());
+                    }
+                }
+            });
+
+        let selected = app.player_browser.selected.as_ref()
+            .and_then(|id| app.player_browser.players.iter().find(|player| &player.user_id == id))
+            .cloned();
+        egui::ScrollArea::vertical()
+            .id_salt("player_details")
+            .auto_shrink([false; 2])
+            .show(&mut columns[1], |ui| match selected {
+                Some(player) => render_player_details(app, ui, ctx, &player),
+                None => {
+                    ui.label("Select a player to see their matches.");
+                }
+            });
+    });
+}
+
+fn render_player_details(app: &mut ReplayApp, ui: &mut egui::Ui, ctx: &Context, player: &PlayerStats) {
+    let user_id = player.user_id.as_str();
+    ui.horizontal(|ui| {
+        app.render_user_avatar(ui, ctx, user_id);
+        ui.vertical(|ui| {
+            ui.heading(app.settings.address_book.display_name(user_id));
+            ui.monospace(user_id);
+            if let Some(notes) = app.settings.address_book.get(user_id).map(|contact| contact.notes.clone()) {
+                if !notes.trim().is_empty() {
+                    ui.label(notes);
+                }
+            }
+        });
+    });
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label(format!("{} matches, {} downloaded", player.matches(), player.local_matches()));
+        if let Some(last_seen) = player.last_seen {
+            ui.separator();
+            ui.label(format!("Last seen {}", last_seen.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")));
+        }
+    });
+
+    ui.horizontal(|ui| {
+        if ui.button("Find all replays").on_hover_text("Search the whole catalogue for this player").clicked() {
+            app.replay_list.filters.user_id = user_id.to_string();
+            app.current_page = Page::Main;
+            app.start_catalogue_search();
+        }
+        if ui.button("Edit contact...").clicked() {
+            app.edit_contact(user_id);
+        }
+        let tracked = is_tracked(&app.settings.tracked_players, user_id);
+        if ui.button(if tracked { "Stop tracking" } else { "Track player" }).clicked() {
+            app.toggle_tracked_player(user_id);
+        }
+    });
+    ui.separator();
+
+    ui.columns(2, |columns| {
+        columns[0].strong("Maps");
+        for (map, count) in player.maps.iter().take(10) {
+            columns[0].label(format!("{} ({})", map, count));
+        }
+        columns[1].strong("Game modes");
+        for (mode, count) in player.modes.iter().take(10) {
+            columns[1].label(format!("{} ({})", mode, count));
+        }
+    });
+    ui.separator();
+
+    ui.strong("Replays");
+    egui::Grid::new("player_replays").num_columns(4).striped(true).show(ui, |ui| {
+        for entry in &player.replays {
+            let replay = &entry.replay;
+            ui.label(&replay.created_date);
+            ui.label(&replay.map_name);
+            ui.label(&replay.game_mode);
+            ui.horizontal(|ui| {
+                if let Some(path) = &entry.local_path {
+                    if ui.small_button("Open folder").clicked() {
+                        if let Err(e) = open::that(path.parent().unwrap_or(path)) {
+                            app.show_error(format!("Failed to open folder: {}", e));
+                        }
+                    }
+                } else if app.downloaded_replays.contains(&replay.id) {
+                    ui.weak("Downloaded");
+                } else if ui.small_button("Download").clicked() {
+                    app.process_online_replay(&replay.id);
+                }
+                if ui.small_button("Details").clicked() {
+                    app.open_replay_details(replay);
+                }
+                if ui.small_button("Copy ID").clicked() {
+                    ctx.copy_text(replay.id.clone());
+                }
+            });
+            ui.end_row();
+        }
+    });
+}
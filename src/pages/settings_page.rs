@@ -1,118 +1,539 @@
 use eframe::egui::{self, Layout, Align};
-use crate::app::ReplayApp;
+use crate::app::{PlatformFilter, ReplayApp};
+use crate::tools::auto_download::AutoDownloadRule;
+use crate::tools::http_client;
+use crate::tools::logging::{self, LogFilter, DEFAULT_LOG_FILTER};
+use crate::tools::tracking::TrackedPlayer;
+use crate::tools::naming::{self, NamingOptions, PLACEHOLDERS};
 
 pub fn render_settings_page(app: &mut ReplayApp, ui: &mut egui::Ui) {
     ui.heading("Settings");
     ui.separator();
-    
-    ui.add_space(8.0);
-    
-    // Download directory settings
-    ui.group(|ui| {
-        ui.vertical(|ui| {
-            ui.heading("Download Directory");
+
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            ui.add_space(8.0);
+
+            // Download directory settings
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.heading("Download Directory");
+                    ui.horizontal(|ui| {
+                        let path_text = app.settings.download_dir.display().to_string();
+                        ui.label("Save replays to:");
+                        ui.add(egui::Label::new(path_text).wrap());
+
+                        if app.styled_button(ui, "Browse").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                                app.settings.download_dir = path;
+                                app.rescan_library();
+                                if let Err(err) = app.save_settings() {
+                                    app.show_error(format!("Error saving settings: {}", err));
+                                } else {
+                                    app.show_success("Settings saved successfully");
+                                }
+                            }
+                        }
+                    });
+
+                    ui.add_space(4.0);
+                    ui.label("This is where downloaded replays will be saved");
+                });
+            });
+
+            ui.add_space(16.0);
+
+            // File naming settings
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.heading("File Naming");
+                    ui.horizontal(|ui| {
+                        ui.label("Template:");
+                        ui.add(egui::TextEdit::singleline(&mut app.settings.naming.template)
+                            .hint_text(naming::DEFAULT_TEMPLATE)
+                            .desired_width(300.0));
+                    });
+                    ui.checkbox(&mut app.settings.naming.utc, "Use UTC for {date}");
+                    ui.horizontal(|ui| {
+                        ui.label("Replace spaces and separators in names with:");
+                        ui.radio_value(&mut app.settings.naming.replacement, '-', "-");
+                        ui.radio_value(&mut app.settings.naming.replacement, '_', "_");
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Presets:");
+                        if ui.small_button("Default").clicked() {
+                            app.settings.naming = NamingOptions::preset(false, false, app.settings.naming.utc);
+                        }
+                        if ui.small_button("Date first").clicked() {
+                            app.settings.naming = NamingOptions::preset(true, false, app.settings.naming.utc);
+                        }
+                        if ui.small_button("ISO 8601").clicked() {
+                            app.settings.naming = NamingOptions::preset(false, true, app.settings.naming.utc);
+                        }
+                    });
+
+                    ui.add_space(4.0);
+                    match naming::preview_filename(&app.settings.naming) {
+                        Ok(preview) => {
+                            ui.label(format!("Example: {}", preview.display()));
+                        }
+                        Err(err) => {
+                            ui.colored_label(ui.style().visuals.error_fg_color, err.to_string());
+                        }
+                    }
+
+                    ui.add_space(4.0);
+                    ui.label(format!(
+                        "Placeholders: {}. Use {{date:%Y-%m-%d}} for a custom date format and / for subfolders.",
+                        PLACEHOLDERS.map(|name| format!("{{{}}}", name)).join(", ")
+                    ));
+                });
+            });
+
+            ui.add_space(16.0);
+
+            // Auto refresh settings
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.heading("Auto Refresh");
+
+                    ui.checkbox(&mut app.settings.auto_refresh_enabled, "Enable auto refresh");
+
+                    ui.add_enabled(
+                        app.settings.auto_refresh_enabled,
+                        egui::Slider::new(&mut app.settings.auto_refresh_interval_mins, 1..=60)
+                            .text("Refresh interval (minutes)")
+                            .clamping(egui::SliderClamping::Always)
+
+                    );
+
+                    ui.add_space(4.0);
+                    ui.label("Automatically refresh the replay list at the specified interval");
+                });
+            });
+
+            ui.add_space(16.0);
+
+            // Auto download settings
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.heading("Auto Download");
+
+                    ui.checkbox(&mut app.settings.auto_download_enabled, "Enable auto download");
+
+                    ui.add_space(4.0);
+                    ui.label("Replays matching any enabled rule are downloaded into the rule's folder. \
+                        Every criterion that is filled in must match; a rule without criteria matches nothing.");
+                    ui.add_space(4.0);
+
+                    let mut remove_rule = None;
+                    ui.add_enabled_ui(app.settings.auto_download_enabled, |ui| {
+                        for (index, rule) in app.settings.auto_download_rules.iter_mut().enumerate() {
+                            if render_rule_editor(ui, index, rule) {
+                                remove_rule = Some(index);
+                            }
+                        }
+                        if ui.button("Add Rule").clicked() {
+                            app.settings.auto_download_rules.push(AutoDownloadRule::default());
+                        }
+                    });
+                    if let Some(index) = remove_rule {
+                        app.settings.auto_download_rules.remove(index);
+                    }
+
+                    ui.add_space(8.0);
+                    ui.add_enabled_ui(app.settings.auto_download_enabled, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Script:");
+                            let mut script = app.settings.auto_download_script.display().to_string();
+                            if ui.add(egui::TextEdit::singleline(&mut script)
+                                .hint_text("Optional .rhai file")
+                                .desired_width(300.0))
+                                .changed()
+                            {
+                                app.settings.auto_download_script = script.into();
+                            }
+                            if ui.button("Browse").clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .add_filter("Rhai script", &["rhai"])
+                                    .pick_file()
+                                {
+                                    app.settings.auto_download_script = path;
+                                }
+                            }
+                            if ui.button("Clear").clicked() {
+                                app.settings.auto_download_script.clear();
+                            }
+                        });
+                        if let Some(error) = app.script_host.error() {
+                            ui.colored_label(ui.style().visuals.error_fg_color, error);
+                        }
+                        ui.label("The script defines fn decide(replay) and is asked about every replay no rule selects. \
+                            Return true to download, a string to choose the file name, or #{ download, filename }. \
+                            Changes to the file are picked up automatically.");
+                    });
+                });
+            });
+
+            ui.add_space(16.0);
+
+            // Watchlist settings
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.heading("Watchlist");
+
+                    ui.checkbox(&mut app.settings.watchlist_auto_download, "Download watched replays before they expire");
+
+                    ui.add_enabled(
+                        app.settings.watchlist_auto_download,
+                        egui::Slider::new(&mut app.settings.watchlist_expiry_window_hours, 1..=168)
+                            .text("Hours before expiry")
+                            .clamping(egui::SliderClamping::Always)
+                    );
+
+                    ui.add_space(4.0);
+                    ui.label("Replays on the watchlist are downloaded once they expire within this window. \
+                        The watch command uses the same settings.");
+                });
+            });
+
+            ui.add_space(16.0);
+
+            // Tracked players
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.heading("Tracked Players");
+
+                    let mut remove_player = None;
+                    egui::Grid::new("tracked_players").num_columns(3).show(ui, |ui| {
+                        for (index, player) in app.settings.tracked_players.iter_mut().enumerate() {
+                            ui.add(egui::TextEdit::singleline(&mut player.user_id)
+                                .hint_text("User ID")
+                                .desired_width(200.0));
+                            ui.add(egui::TextEdit::singleline(&mut player.nickname)
+                                .hint_text("Nickname")
+                                .desired_width(160.0));
+                            if ui.small_button("Remove").clicked() {
+                                remove_player = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    if let Some(index) = remove_player {
+                        app.settings.tracked_players.remove(index);
+                    }
+                    if ui.button("Add Player").clicked() {
+                        app.settings.tracked_players.push(TrackedPlayer::default());
+                    }
+
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label("Log file:");
+                        let mut log_file = app.settings.tracking_log_file.display().to_string();
+                        if ui.add(egui::TextEdit::singleline(&mut log_file)
+                            .hint_text("Optional")
+                            .desired_width(300.0))
+                            .changed()
+                        {
+                            app.settings.tracking_log_file = log_file.into();
+                        }
+                        if ui.button("Browse").clicked() {
+                            if let Some(path) = rfd::FileDialog::new()
+                                .set_file_name("tracked_players.log")
+                                .save_file()
+                            {
+                                app.settings.tracking_log_file = path;
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Webhook URL:");
+                        ui.add(egui::TextEdit::singleline(&mut app.settings.tracking_webhook_url)
+                            .hint_text("Optional, e.g. a Discord webhook")
+                            .desired_width(300.0));
+                    });
+
+                    ui.add_space(4.0);
+                    ui.label("You are alerted when a tracked player appears on the first page of replays or in a live match. \
+                        Right-click an avatar to track or stop tracking a player.");
+                });
+            });
+
+            ui.add_space(16.0);
+
+            // Address book
+            ui.group(|ui| {
+                ui.vertical(|ui| {
+                    ui.heading(format!("Address Book ({})", app.settings.address_book.len()));
+
+                    let mut edit = None;
+                    egui::Grid::new("address_book").num_columns(3).striped(true).show(ui, |ui| {
+                        for (user_id, contact) in app.settings.address_book.iter() {
+                            ui.label(contact.label());
+                            ui.monospace(user_id);
+                            if ui.small_button("Edit").clicked() {
+                                edit = Some(user_id.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    if let Some(user_id) = edit {
+                        app.edit_contact(&user_id);
+                    }
+
+                    ui.add_space(4.0);
+                    ui.label("Right-click an avatar and choose \"Edit contact...\" to name a player. \
+                        Nicknames, team tags and notes can be searched with the User ID filter.");
+                });
+            });
+
+            ui.add_space(16.0);
+            ui.separator();
+            ui.add_space(8.0);
+
+            ui.heading("Mod.io API Settings");
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.label("API URL:");
+                ui.add(egui::TextEdit::singleline(&mut app.settings.modio_api_url)
+                    .desired_width(300.0));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("API Token:");
+                ui.add(egui::TextEdit::singleline(&mut app.settings.modio_api_token)
+                    .password(true)
+                    .desired_width(300.0));
+            });
+
+            ui.add_space(4.0);
+            ui.label("Configure your mod.io API credentials to see mod details.");
+            ui.label("You can get an API key from https://mod.io/apikey");
+
+            ui.add_space(16.0);
+            ui.separator();
+            ui.add_space(8.0);
+
+            ui.heading("Network");
+            ui.add_space(8.0);
+
+            ui.add(egui::Slider::new(&mut app.settings.network.connect_timeout_secs, 1..=60)
+                .text("Connect timeout (seconds)")
+                .clamping(egui::SliderClamping::Always));
+            ui.add(egui::Slider::new(&mut app.settings.network.request_timeout_secs, 5..=300)
+                .text("Request timeout (seconds)")
+                .clamping(egui::SliderClamping::Always))
+                .on_hover_text("Time to wait for a response, and for more data while a replay file downloads");
+
             ui.horizontal(|ui| {
-                let path_text = app.settings.download_dir.display().to_string();
-                ui.label("Save replays to:");
-                ui.add(egui::Label::new(path_text).wrap());
-                
-                if app.styled_button(ui, "Browse").clicked() {
-                    if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                        app.settings.download_dir = path;
-                        if let Err(err) = app.save_settings() {
+                ui.label("User agent:");
+                ui.add(egui::TextEdit::singleline(&mut app.settings.network.user_agent)
+                    .hint_text(http_client::DEFAULT_USER_AGENT)
+                    .desired_width(300.0));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Proxy URL:");
+                ui.add(egui::TextEdit::singleline(&mut app.settings.network.proxy_url)
+                    .hint_text("http://proxy.example:3128")
+                    .desired_width(300.0));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("No proxy for:");
+                ui.add(egui::TextEdit::singleline(&mut app.settings.network.no_proxy)
+                    .hint_text("localhost, .internal.example")
+                    .desired_width(300.0));
+            });
+
+            ui.add_space(4.0);
+            ui.label("Extra root certificates:");
+            let mut remove_cert = None;
+            for (index, path) in app.settings.network.extra_root_certs.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.monospace(path.display().to_string());
+                    if ui.small_button("Remove").clicked() {
+                        remove_cert = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = remove_cert {
+                app.settings.network.extra_root_certs.remove(index);
+            }
+            if ui.button("Add Certificate").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Certificates", &["pem", "crt", "cer", "der"])
+                    .pick_file() {
+                    app.settings.network.extra_root_certs.push(path);
+                }
+            }
+
+            ui.add_space(4.0);
+            ui.checkbox(&mut app.settings.network.accept_invalid_certs, "Accept self-signed certificates from the replay server");
+            if app.settings.network.accept_invalid_certs {
+                ui.colored_label(
+                    ui.style().visuals.warn_fg_color,
+                    "Certificate validation of the replay server is disabled. Only use this for a trusted local mirror."
+                );
+            }
+
+            ui.add_space(4.0);
+            ui.label("Applies to the replay API, avatars, mod.io and update checks.");
+
+            ui.add_space(16.0);
+            ui.separator();
+            ui.add_space(8.0);
+
+            ui.heading("Logging");
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Log level:");
+                ui.add(egui::TextEdit::singleline(&mut app.settings.log_level)
+                    .hint_text(DEFAULT_LOG_FILTER)
+                    .desired_width(300.0));
+            });
+            ui.label("One of error, warn, info, debug or trace, optionally followed by levels for \
+                single modules, e.g. \"info,tools::batch=debug\".");
+
+            ui.horizontal(|ui| {
+                if ui.button("View Log").clicked() {
+                    app.log_viewer.open = true;
+                }
+                if let Some(path) = logging::log_file_path() {
+                    if ui.button("Open Log Folder").on_hover_text(path.display().to_string()).clicked() {
+                        if let Err(e) = open::that(path.parent().unwrap_or(&path)) {
+                            app.show_error(format!("Failed to open folder: {}", e));
+                        }
+                    }
+                }
+            });
+
+            ui.add_space(8.0);
+
+            // Apply button
+            ui.horizontal(|ui| {
+                ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
+                    if app.styled_button(ui, "Apply").clicked() {
+                        if let Err(err) = app.settings.naming.validate() {
+                            app.show_error(format!("Invalid file name template: {}", err));
+                        } else if let Err(err) = http_client::configure(&app.settings.network) {
+                            app.show_error(format!("Invalid network settings: {}", err));
+                        } else if let Err(err) = app.settings.log_level.parse::<LogFilter>().map(logging::set_filter) {
+                            app.show_error(format!("Invalid log level: {}", err));
+                        } else if let Err(err) = app.save_settings() {
                             app.show_error(format!("Error saving settings: {}", err));
                         } else {
+                            // A template with more or fewer folders changes how deep the library is scanned.
+                            app.rescan_library();
                             app.show_success("Settings saved successfully");
                         }
                     }
-                }
+                });
             });
-            
-            ui.add_space(4.0);
-            ui.label("This is where downloaded replays will be saved");
         });
-    });
-    
-    ui.add_space(16.0);
-    
-    // Auto refresh settings
-    ui.group(|ui| {
-        ui.vertical(|ui| {
-            ui.heading("Auto Refresh");
-            
-            ui.checkbox(&mut app.settings.auto_refresh_enabled, "Enable auto refresh");
-            
-            ui.add_enabled(
-                app.settings.auto_refresh_enabled,
-                egui::Slider::new(&mut app.settings.auto_refresh_interval_mins, 1..=60)
-                    .text("Refresh interval (minutes)")
-                    .clamping(egui::SliderClamping::Always)
-
-            );
-            
-            ui.add_space(4.0);
-            ui.label("Automatically refresh the replay list at the specified interval");
-        });
-    });
+}
 
-    ui.add_space(16.0);
+/// Editor for one auto-download rule. Returns `true` when the rule should be removed.
+fn render_rule_editor(ui: &mut egui::Ui, index: usize, rule: &mut AutoDownloadRule) -> bool {
+    let mut remove = false;
 
-    // Auto download settings
-    ui.group(|ui| {
-        ui.vertical(|ui| {
-            ui.heading("Auto Download");
-            
-            ui.checkbox(&mut app.settings.auto_download_enabled, "Enable auto download");
-            
-            ui.add_space(4.0);
-            ui.label("User ID trigger:");
-            ui.add_enabled(
-                app.settings.auto_download_enabled,
-                egui::TextEdit::singleline(&mut app.settings.auto_download_trigger_user_id)
-                    .hint_text("Enter user ID to auto-download")
-            );
-            
-            ui.add_space(4.0);
-            ui.label("Automatically download replays containing the specified user ID");
-        });
-    });
+    egui::CollapsingHeader::new(format!("{}{}", rule.name, if rule.enabled { "" } else { " (disabled)" }))
+        .id_salt(("auto_download_rule", index))
+        .show(ui, |ui| {
+            egui::Grid::new(("auto_download_rule_grid", index))
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut rule.name);
+                    ui.end_row();
 
-    ui.add_space(16.0);
-    ui.separator();
-    ui.add_space(8.0);
-
-    ui.heading("Mod.io API Settings");
-    ui.add_space(8.0);
-
-    ui.horizontal(|ui| {
-        ui.label("API URL:");
-        ui.add(egui::TextEdit::singleline(&mut app.settings.modio_api_url)
-            .desired_width(300.0));
-    });
-
-    ui.horizontal(|ui| {
-        ui.label("API Token:");
-        ui.add(egui::TextEdit::singleline(&mut app.settings.modio_api_token)
-            .password(true)
-            .desired_width(300.0));
-    });
-
-    ui.add_space(4.0);
-    ui.label("Configure your mod.io API credentials to see mod details.");
-    ui.label("You can get an API key from https://mod.io/apikey");
-
-    ui.add_space(8.0);
-    
-    // Apply button
-    ui.horizontal(|ui| {
-        ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
-            if app.styled_button(ui, "Apply").clicked() {
-                if let Err(err) = app.save_settings() {
-                    app.show_error(format!("Error saving settings: {}", err));
-                } else {
-                    app.show_success("Settings saved successfully");
-                }
+                    ui.label("Enabled:");
+                    ui.checkbox(&mut rule.enabled, "");
+                    ui.end_row();
+
+                    ui.label("User IDs:");
+                    ui.add(egui::TextEdit::singleline(&mut rule.user_ids)
+                        .hint_text("Any of these, separated by commas")
+                        .desired_width(300.0));
+                    ui.end_row();
+
+                    ui.label("Map:");
+                    ui.add(egui::TextEdit::singleline(&mut rule.map_name).hint_text("Any"));
+                    ui.end_row();
+
+                    ui.label("Game mode:");
+                    ui.add(egui::TextEdit::singleline(&mut rule.game_mode).hint_text("Any"));
+                    ui.end_row();
+
+                    ui.label("Platform:");
+                    egui::ComboBox::new(("auto_download_rule_platform", index), "")
+                        .selected_text(match rule.platform {
+                            PlatformFilter::All => "All",
+                            PlatformFilter::Quest => "Quest",
+                            PlatformFilter::PC => "PC",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut rule.platform, PlatformFilter::All, "All");
+                            ui.selectable_value(&mut rule.platform, PlatformFilter::Quest, "Quest");
+                            ui.selectable_value(&mut rule.platform, PlatformFilter::PC, "PC");
+                        });
+                    ui.end_row();
+
+                    ui.label("Match type:");
+                    egui::ComboBox::new(("auto_download_rule_competitive", index), "")
+                        .selected_text(match rule.competitive {
+                            None => "Any",
+                            Some(true) => "Competitive",
+                            Some(false) => "Casual",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut rule.competitive, None, "Any");
+                            ui.selectable_value(&mut rule.competitive, Some(true), "Competitive");
+                            ui.selectable_value(&mut rule.competitive, Some(false), "Casual");
+                        });
+                    ui.end_row();
+
+                    ui.label("Mod IDs:");
+                    ui.add(egui::TextEdit::singleline(&mut rule.mod_ids)
+                        .hint_text("All of these, separated by commas")
+                        .desired_width(300.0));
+                    ui.end_row();
+
+                    ui.label("Minimum players:");
+                    ui.add(egui::DragValue::new(&mut rule.min_players).range(0..=100));
+                    ui.end_row();
+
+                    ui.label("Folder:");
+                    ui.horizontal(|ui| {
+                        let mut folder = rule.target_dir.display().to_string();
+                        if ui.add(egui::TextEdit::singleline(&mut folder)
+                            .hint_text("Download directory")
+                            .desired_width(220.0))
+                            .changed()
+                        {
+                            rule.target_dir = folder.into();
+                        }
+                        if ui.button("Browse").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                                rule.target_dir = path;
+                            }
+                        }
+                    });
+                    ui.end_row();
+                });
+
+            if rule.is_empty() {
+                ui.colored_label(ui.style().visuals.warn_fg_color, "This rule has no criteria and will not match any replay.");
+            }
+            if ui.button("Remove Rule").clicked() {
+                remove = true;
             }
         });
-    });
-}
\ No newline at end of file
+
+    remove
+}
@@ -0,0 +1,85 @@
+use eframe::egui::{self, Context, Key, Modifiers};
+
+use crate::app::{Page, ReplayApp};
+
+/// Number keys switching pages, in the order of the top bar.
+const PAGE_KEYS: [(Key, Page); 6] = [
+    (Key::Num1, Page::Main),
+    (Key::Num2, Page::Live),
+    (Key::Num3, Page::Players),
+    (Key::Num4, Page::ProcessLocal),
+    (Key::Num5, Page::Manage),
+    (Key::Num6, Page::Settings),
+];
+
+/// Shown in the overlay, grouped by where the keys work.
+const SHORTCUTS: [(&str, &[(&str, &str)]); 3] = [
+    ("Everywhere", &[
+        ("1 - 6", "Replays, Live, Players, Local Processing, Manage, Settings"),
+        ("? / F1", "Show or hide this overview"),
+        ("Esc", "Close this overview or leave a text field"),
+    ]),
+    ("Replays", &[
+        ("Up / Down, K / J", "Move through the replay cards"),
+        ("Enter", "Download & process the highlighted replay"),
+        ("Space", "Tick or untick the highlighted replay"),
+        ("/", "Focus the filter fields"),
+        ("Left / Right", "Previous or next page"),
+    ]),
+    ("Manage", &[
+        ("Up / Down, K / J", "Move through the downloaded replays"),
+        ("Delete", "Delete the highlighted replay, after confirming"),
+        ("Enter / Esc", "Confirm or cancel the deletion"),
+    ]),
+];
+
+/// Shortcuts that work on every page. Keys go to text fields while one has focus.
+pub fn handle_shortcuts(app: &mut ReplayApp, ctx: &Context) {
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+
+    ctx.input_mut(|input| {
+        if input.consume_key(Modifiers::NONE, Key::F1) || input.key_pressed(Key::Questionmark) {
+            app.shortcuts_open = !app.shortcuts_open;
+        }
+        if app.shortcuts_open && input.consume_key(Modifiers::NONE, Key::Escape) {
+            app.shortcuts_open = false;
+        }
+    });
+
+    let page = ctx.input_mut(|input| {
+        PAGE_KEYS.iter().find(|(key, _)| input.consume_key(Modifiers::NONE, *key)).map(|(_, page)| *page)
+    });
+    if let Some(page) = page {
+        app.open_page(page);
+    }
+}
+
+pub fn render_shortcuts_dialog(app: &mut ReplayApp, ctx: &Context) {
+    if !app.shortcuts_open {
+        return;
+    }
+
+    let mut open = app.shortcuts_open;
+    egui::Window::new("Keyboard Shortcuts")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            for (section, keys) in SHORTCUTS {
+                ui.strong(section);
+                egui::Grid::new(section).num_columns(2).striped(true).show(ui, |ui| {
+                    for (key, description) in keys {
+                        ui.monospace(*key);
+                        ui.label(*description);
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(8.0);
+            }
+            ui.weak("Shortcuts are paused while a text field has focus.");
+        });
+    app.shortcuts_open = open;
+}
@@ -0,0 +1,93 @@
+use eframe::egui::{self, Context};
+
+use crate::app::ReplayApp;
+use crate::tools::watchlist::{expiry_text, time_until_expiry};
+
+pub fn render_watchlist_dialog(app: &mut ReplayApp, ctx: &Context) {
+    if !app.watchlist_open {
+        return;
+    }
+
+    let mut open = app.watchlist_open;
+    let mut download: Vec<String> = Vec::new();
+    let mut remove: Vec<String> = Vec::new();
+
+    egui::Window::new("Watchlist")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_size([460.0, 360.0])
+        .show(ctx, |ui| {
+            if app.settings.watchlist.is_empty() {
+                ui.label("No replays on the watchlist. Select replays on the main page and choose \"Add to watchlist\".");
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("Download all").clicked() {
+                    download = app.settings.watchlist.iter()
+                        .filter(|entry| !app.downloaded_replays.contains(&entry.id))
+                        .map(|entry| entry.id.clone())
+                        .collect();
+                }
+                if ui.button("Remove downloaded").clicked() {
+                    remove = app.settings.watchlist.iter()
+                        .filter(|entry| app.downloaded_replays.contains(&entry.id))
+                        .map(|entry| entry.id.clone())
+                        .collect();
+                }
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    for entry in &app.settings.watchlist {
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                ui.strong(format!("{} - {}", entry.map_name, entry.game_mode));
+                                ui.horizontal(|ui| {
+                                    ui.monospace(&entry.id);
+                                    match time_until_expiry(&entry.expires) {
+                                        Some(remaining) if entry.is_due(app.settings.watchlist_expiry_window_hours) => {
+                                            ui.colored_label(
+                                                ui.style().visuals.warn_fg_color,
+                                                expiry_text(remaining),
+                                            );
+                                        }
+                                        Some(remaining) => {
+                                            ui.weak(expiry_text(remaining));
+                                        }
+                                        None => {
+                                            ui.weak("expiry unknown");
+                                        }
+                                    }
+                                });
+                            });
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.button("Remove").clicked() {
+                                    remove.push(entry.id.clone());
+                                }
+                                if app.downloaded_replays.contains(&entry.id) {
+                                    ui.add_enabled(false, egui::Button::new("Downloaded"));
+                                } else if ui.button("Download").clicked() {
+                                    download.push(entry.id.clone());
+                                }
+                            });
+                        });
+                        ui.separator();
+                    }
+                });
+        });
+
+    app.watchlist_open = open;
+    if !download.is_empty() {
+        app.queue_downloads(&download);
+    }
+    if !remove.is_empty() {
+        app.settings.watchlist.retain(|entry| !remove.contains(&entry.id));
+        if let Err(err) = app.save_settings() {
+            app.show_error(format!("Error saving settings: {}", err));
+        }
+    }
+}
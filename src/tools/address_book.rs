@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tools::listing::contains_ignore_case;
+
+/// What the team knows about a player.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Contact {
+    pub nickname: String,
+    pub team: String,
+    pub notes: String,
+}
+
+impl Contact {
+    pub fn is_empty(&self) -> bool {
+        self.nickname.trim().is_empty() && self.team.trim().is_empty() && self.notes.trim().is_empty()
+    }
+
+    /// Nickname with the team tag, such as `[ABC] Nick`. Empty if neither is set.
+    pub fn label(&self) -> String {
+        match (self.team.trim(), self.nickname.trim()) {
+            ("", nickname) => nickname.to_string(),
+            (team, "") => format!("[{}]", team),
+            (team, nickname) => format!("[{}] {}", team, nickname),
+        }
+    }
+}
+
+/// Contacts by user ID, stored in the settings file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AddressBook {
+    contacts: BTreeMap<String, Contact>,
+}
+
+impl AddressBook {
+    pub fn get(&self, user_id: &str) -> Option<&Contact> {
+        self.contacts.get(user_id)
+    }
+
+    /// Stores `contact` for `user_id`, or removes the entry if the contact is empty.
+    pub fn set(&mut self, user_id: &str, contact: Contact) {
+        if contact.is_empty() {
+            self.contacts.remove(user_id);
+        } else {
+            self.contacts.insert(user_id.to_string(), contact);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Contact)> {
+        self.contacts.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.contacts.len()
+    }
+
+    /// Name to show for `user_id`: the contact label, or the ID itself.
+    pub fn display_name(&self, user_id: &str) -> String {
+        match self.get(user_id).map(Contact::label) {
+            Some(label) if !label.is_empty() => label,
+            _ => user_id.to_string(),
+        }
+    }
+
+    /// Whether `query` is part of the user ID or of its nickname, team tag or notes.
+    pub fn user_matches(&self, user_id: &str, query: &str) -> bool {
+        contains_ignore_case(user_id, query)
+            || self.get(user_id).is_some_and(|contact| {
+                contains_ignore_case(&contact.nickname, query)
+                    || contains_ignore_case(&contact.team, query)
+                    || contains_ignore_case(&contact.notes, query)
+            })
+    }
+}
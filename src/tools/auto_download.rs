@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::tools::listing::{contains_ignore_case, PlatformFilter};
+use crate::tools::replay_processor::{parse_mod_ids, ApiReplay, ReplayItem};
+use crate::tools::script::{DownloadScript, ScriptDecision};
+
+/// One auto-download rule. Every criterion that is set must match; map and game mode are
+/// case-insensitive substring matches like the replay list filters.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoDownloadRule {
+    pub name: String,
+    pub enabled: bool,
+    /// User IDs separated by commas or spaces. The replay must include at least one of them,
+    /// compared exactly but ignoring case.
+    pub user_ids: String,
+    pub map_name: String,
+    pub game_mode: String,
+    pub platform: PlatformFilter,
+    /// `Some(true)` for competitive matches only, `Some(false)` for casual only.
+    pub competitive: Option<bool>,
+    /// Workshop mod IDs separated by commas or spaces. The replay must use all of them.
+    pub mod_ids: String,
+    pub min_players: usize,
+    /// Folder for matching replays. Relative paths are inside the download directory, empty
+    /// means the download directory itself.
+    pub target_dir: PathBuf,
+}
+
+impl Default for AutoDownloadRule {
+    fn default() -> Self {
+        Self {
+            name: "New rule".to_string(),
+            enabled: true,
+            user_ids: String::new(),
+            map_name: String::new(),
+            game_mode: String::new(),
+            platform: PlatformFilter::All,
+            competitive: None,
+            mod_ids: String::new(),
+            min_players: 0,
+            target_dir: PathBuf::new(),
+        }
+    }
+}
+
+fn split_list(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split([',', ' ', '\n', '\t'])
+        .filter(|item| !item.is_empty())
+        .map(str::to_lowercase)
+}
+
+impl AutoDownloadRule {
+    /// Rule selecting replays that include `user_id`, equivalent to the single trigger user
+    /// ID of older settings files.
+    pub fn for_user(user_id: &str) -> Self {
+        Self {
+            name: format!("Replays with {}", user_id),
+            user_ids: user_id.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether no criterion is set. Such a rule matches nothing rather than every replay.
+    pub fn is_empty(&self) -> bool {
+        split_list(&self.user_ids).next().is_none()
+            && self.map_name.is_empty()
+            && self.game_mode.is_empty()
+            && self.platform == PlatformFilter::All
+            && self.competitive.is_none()
+            && split_list(&self.mod_ids).next().is_none()
+            && self.min_players == 0
+    }
+
+    pub fn matches(&self, replay: &ReplayItem) -> bool {
+        if !self.enabled || self.is_empty() {
+            return false;
+        }
+
+        let user_matches = {
+            let mut wanted = split_list(&self.user_ids).peekable();
+            wanted.peek().is_none() || wanted.any(|id| replay.users.iter().any(|user| user.eq_ignore_ascii_case(&id)))
+        };
+
+        let mods: Vec<String> = parse_mod_ids(&replay.workshop_mods)
+            .into_iter()
+            .map(|id| id.to_lowercase())
+            .collect();
+        let mods_match = split_list(&self.mod_ids).all(|id| {
+            let id = id.strip_prefix("ugc").unwrap_or(&id).to_string();
+            mods.contains(&id)
+        });
+
+        user_matches
+            && mods_match
+            && contains_ignore_case(&replay.map_name, &self.map_name)
+            && contains_ignore_case(&replay.game_mode, &self.game_mode)
+            && self.platform.shack().is_none_or(|shack| replay.shack == shack)
+            && self.competitive.is_none_or(|competitive| replay.competitive == competitive)
+            && replay.users.len() >= self.min_players
+    }
+
+    /// Folder matching replays are saved to.
+    pub fn download_dir(&self, base: &Path) -> PathBuf {
+        if self.target_dir.as_os_str().is_empty() {
+            base.to_path_buf()
+        } else {
+            base.join(&self.target_dir)
+        }
+    }
+}
+
+/// First enabled rule that selects `replay`.
+pub fn matching_rule<'a>(rules: &'a [AutoDownloadRule], replay: &ReplayItem) -> Option<&'a AutoDownloadRule> {
+    rules.iter().find(|rule| rule.matches(replay))
+}
+
+/// Replays selected for download, grouped by target folder.
+#[derive(Default)]
+pub struct AutoDownloadPlan {
+    pub jobs: Vec<(PathBuf, Vec<ApiReplay>)>,
+    /// File names chosen by the download script, by replay ID.
+    pub file_names: HashMap<String, PathBuf>,
+    /// Replays the script failed on, with the error.
+    pub script_errors: Vec<(String, String)>,
+}
+
+impl AutoDownloadPlan {
+    fn add(&mut self, dir: PathBuf, replay: ApiReplay) {
+        match self.jobs.iter_mut().find(|(job_dir, _)| *job_dir == dir) {
+            Some((_, replays)) => replays.push(replay),
+            None => self.jobs.push((dir, vec![replay])),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.iter().map(|(_, replays)| replays.len()).sum()
+    }
+}
+
+/// Decides which of `replays` to download. Rules are checked first; the script, if any, is
+/// asked about replays no rule selects and saves them in `download_dir`.
+pub fn plan_downloads(
+    rules: &[AutoDownloadRule],
+    script: Option<&DownloadScript>,
+    download_dir: &Path,
+    replays: impl IntoIterator<Item = ApiReplay>,
+) -> AutoDownloadPlan {
+    let mut plan = AutoDownloadPlan::default();
+    for replay in replays {
+        if let Some(rule) = matching_rule(rules, &ReplayItem::from(replay.clone())) {
+            plan.add(rule.download_dir(download_dir), replay);
+            continue;
+        }
+        let Some(script) = script else {
+            continue;
+        };
+        match script.decide(&replay) {
+            Ok(ScriptDecision::Skip) => {}
+            Ok(ScriptDecision::Download { file_name }) => {
+                if let Some(file_name) = file_name {
+                    plan.file_names.insert(replay.id.clone(), file_name);
+                }
+                plan.add(download_dir.to_path_buf(), replay);
+            }
+            Err(e) => plan.script_errors.push((replay.id.clone(), e.to_string())),
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::replay_processor::test_replays::api_replay;
+
+    fn replay(id: &str) -> ApiReplay {
+        ApiReplay {
+            workshop_mods: "AdditionalMods=UGC1234/Mod1-UGC5678/Mod2".to_string(),
+            competitive: true,
+            modcount: 2,
+            ..api_replay(id, &["Alice123", "bob"])
+        }
+    }
+
+    fn matches(rule: AutoDownloadRule) -> bool {
+        rule.matches(&ReplayItem::from(replay("abc123")))
+    }
+
+    #[test]
+    fn empty_or_disabled_rules_match_nothing() {
+        assert!(!matches(AutoDownloadRule::default()));
+        assert!(!matches(AutoDownloadRule { user_ids: " , ".to_string(), ..Default::default() }));
+        assert!(!matches(AutoDownloadRule { enabled: false, ..AutoDownloadRule::for_user("bob") }));
+    }
+
+    #[test]
+    fn any_listed_user_matches_ignoring_case() {
+        assert!(matches(AutoDownloadRule::for_user("ALICE123")));
+        assert!(matches(AutoDownloadRule { user_ids: "carol, bob".to_string(), ..Default::default() }));
+        assert!(!matches(AutoDownloadRule::for_user("ALICE")), "user IDs must match exactly");
+        assert!(!matches(AutoDownloadRule::for_user("carol")));
+    }
+
+    #[test]
+    fn every_listed_mod_must_be_used() {
+        let rule = |mod_ids: &str| AutoDownloadRule { mod_ids: mod_ids.to_string(), ..Default::default() };
+        assert!(matches(rule("1234")));
+        assert!(matches(rule("UGC1234 ugc5678")));
+        assert!(!matches(rule("1234,9999")));
+    }
+
+    #[test]
+    fn all_criteria_must_match() {
+        let rule = AutoDownloadRule {
+            map_name: "data".to_string(),
+            game_mode: "snd".to_string(),
+            platform: PlatformFilter::PC,
+            competitive: Some(true),
+            min_players: 2,
+            ..AutoDownloadRule::for_user("bob")
+        };
+        assert!(matches(rule.clone()));
+        assert!(!matches(AutoDownloadRule { platform: PlatformFilter::Quest, ..rule.clone() }));
+        assert!(!matches(AutoDownloadRule { competitive: Some(false), ..rule.clone() }));
+        assert!(!matches(AutoDownloadRule { min_players: 3, ..rule.clone() }));
+        assert!(!matches(AutoDownloadRule { map_name: "bridge".to_string(), ..rule }));
+    }
+
+    #[test]
+    fn plans_matching_replays_into_rule_folders() {
+        let rules = [
+            AutoDownloadRule { target_dir: PathBuf::from("friends"), ..AutoDownloadRule::for_user("carol") },
+            AutoDownloadRule::for_user("bob"),
+        ];
+        let replays = [replay("abc123"), api_replay("other", &["carol"]), ApiReplay { users: None, ..replay("nobody") }];
+
+        let plan = plan_downloads(&rules, None, Path::new("downloads"), replays);
+        let jobs: Vec<(PathBuf, Vec<String>)> = plan
+            .jobs
+            .into_iter()
+            .map(|(dir, replays)| (dir, replays.into_iter().map(|replay| replay.id).collect()))
+            .collect();
+        assert_eq!(jobs, [
+            (PathBuf::from("downloads"), vec!["abc123".to_string()]),
+            (Path::new("downloads").join("friends"), vec!["other".to_string()]),
+        ]);
+    }
+}
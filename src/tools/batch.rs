@@ -0,0 +1,213 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    path::PathBuf,
+};
+
+use rayon::prelude::*;
+
+use crate::tools::error::{ErrorKind, ReplayError};
+use crate::tools::library::{record_download, scan_downloaded_ids, write_downloaded_replay};
+use crate::tools::naming::{replay_filename, NamingOptions};
+use crate::tools::replay_processor::{
+    download_replay_with_info, find_replays, is_valid_replay_id, ApiReplay, MetaData,
+};
+
+pub const DEFAULT_JOBS: usize = 3;
+
+#[derive(Clone, Debug)]
+pub struct BatchOptions {
+    pub download_dir: PathBuf,
+    pub naming: NamingOptions,
+    /// Maximum number of replays downloaded at the same time.
+    pub jobs: usize,
+    /// Skip IDs that already have a `.replay` file in `download_dir`.
+    pub skip_existing: bool,
+    /// File names chosen by a download script, relative to `download_dir`. Replays without an
+    /// entry are named with `naming`.
+    pub file_names: HashMap<String, PathBuf>,
+}
+
+#[derive(Clone, Debug)]
+pub enum BatchEvent {
+    Skipped { id: String, reason: String },
+    Started { id: String },
+    Progress { id: String, current: usize, total: usize },
+    /// The replay was downloaded and built; sent before it is written to disk.
+    Metadata { id: String, meta: Box<MetaData>, info: Box<ApiReplay> },
+    Finished { id: String, path: PathBuf, bytes: usize },
+    Failed { id: String, error: ReplayError },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BatchSummary {
+    pub downloaded: Vec<(String, PathBuf)>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<(String, ReplayError)>,
+}
+
+impl BatchSummary {
+    /// The kind shared by every failure, or `None` if nothing failed or the causes differ.
+    pub fn failure_kind(&self) -> Option<ErrorKind> {
+        let kind = self.failed.first()?.1.kind;
+        self.failed.iter().all(|(_, error)| error.kind == kind).then_some(kind)
+    }
+}
+
+/// Splits free-form text (arguments, a file or stdin) into unique replay IDs.
+/// IDs may be separated by whitespace or commas; `#` starts a comment.
+pub fn parse_replay_ids(text: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        for id in line.split(|c: char| c.is_whitespace() || c == ',') {
+            if !id.is_empty() && !ids.iter().any(|existing| existing == id) {
+                ids.push(id.to_string());
+            }
+        }
+    }
+    ids
+}
+
+/// Downloads `replay_ids` with at most `options.jobs` running concurrently.
+///
+/// The listing is walked once for the whole batch. `on_event` is called from worker
+/// threads as each replay progresses.
+pub fn run_batch(
+    replay_ids: &[String],
+    options: &BatchOptions,
+    on_event: &(dyn Fn(BatchEvent) + Send + Sync),
+) -> Result<BatchSummary, Box<dyn Error + Send + Sync>> {
+    let mut summary = BatchSummary::default();
+    let existing = if options.skip_existing {
+        scan_downloaded_ids(&options.download_dir, options.naming.folder_depth())
+    } else {
+        Default::default()
+    };
+
+    let mut pending = Vec::new();
+    for id in replay_ids {
+        if !is_valid_replay_id(id) {
+            let error = ReplayError::new(ErrorKind::NotFound, "Invalid replay id");
+            on_event(BatchEvent::Failed { id: id.clone(), error: error.clone() });
+            summary.failed.push((id.clone(), error));
+        } else if existing.contains(id) {
+            on_event(BatchEvent::Skipped { id: id.clone(), reason: "Already downloaded".to_string() });
+            summary.skipped.push(id.clone());
+        } else {
+            pending.push(id.clone());
+        }
+    }
+
+    if pending.is_empty() {
+        return Ok(summary);
+    }
+
+    let mut listing = find_replays(&pending)?;
+    let mut work = Vec::new();
+    for id in pending {
+        match listing.remove(&id) {
+            Some(info) => work.push(info),
+            None => {
+                let error = ReplayError::new(ErrorKind::NotFound, "Recording not available");
+                on_event(BatchEvent::Failed { id: id.clone(), error: error.clone() });
+                summary.failed.push((id, error));
+            }
+        }
+    }
+
+    let downloaded = download_listed(work, options, on_event)?;
+    summary.downloaded.extend(downloaded.downloaded);
+    summary.failed.extend(downloaded.failed);
+    Ok(summary)
+}
+
+/// Downloads replays whose listing entries are already known, with at most
+/// `options.jobs` running concurrently. `options.skip_existing` is not checked here.
+pub fn download_listed(
+    replays: Vec<ApiReplay>,
+    options: &BatchOptions,
+    on_event: &(dyn Fn(BatchEvent) + Send + Sync),
+) -> Result<BatchSummary, Box<dyn Error + Send + Sync>> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs.max(1))
+        .build()?;
+
+    let results: Vec<(String, Result<PathBuf, ReplayError>)> = pool.install(|| {
+        replays.into_par_iter()
+            .map(|info| {
+                let id = info.id.clone();
+                on_event(BatchEvent::Started { id: id.clone() });
+                let progress_id = id.clone();
+                let progress = Box::new(move |current: usize, total: usize| {
+                    on_event(BatchEvent::Progress { id: progress_id.clone(), current, total });
+                });
+                let result = download_and_save(info, options, progress, on_event);
+
+                match &result {
+                    Ok((path, bytes)) => on_event(BatchEvent::Finished { id: id.clone(), path: path.clone(), bytes: *bytes }),
+                    Err(error) => on_event(BatchEvent::Failed { id: id.clone(), error: error.clone() }),
+                }
+                (id, result.map(|(path, _)| path))
+            })
+            .collect()
+    });
+
+    let mut summary = BatchSummary::default();
+    for (id, result) in results {
+        match result {
+            Ok(path) => summary.downloaded.push((id, path)),
+            Err(error) => summary.failed.push((id, error)),
+        }
+    }
+
+    Ok(summary)
+}
+
+fn download_and_save<'a>(
+    info: ApiReplay,
+    options: &BatchOptions,
+    progress: Box<dyn Fn(usize, usize) + Send + Sync + 'a>,
+    on_event: &(dyn Fn(BatchEvent) + Send + Sync),
+) -> Result<(PathBuf, usize), ReplayError> {
+    let replay = download_replay_with_info(info, Some(progress))
+        .map_err(|e| ReplayError::from_boxed(e).context("Failed to download replay data"))?;
+    on_event(BatchEvent::Metadata {
+        id: replay.info.id.clone(),
+        meta: Box::new(replay.meta.clone()),
+        info: Box::new(replay.info.clone()),
+    });
+
+    let filename = match options.file_names.get(&replay.info.id) {
+        Some(filename) => filename.clone(),
+        None => replay_filename(&replay.meta, &replay.info, &options.naming)
+            .map_err(|e| ReplayError::new(ErrorKind::Parse, e.to_string()))?,
+    };
+    let path = write_downloaded_replay(&options.download_dir.join(filename), &replay.info.id, &replay.data)
+        .map_err(|e| ReplayError::new(ErrorKind::Write, format!("Failed to save replay file: {}", e)))?;
+    // Without an index entry the ID is guessed from the file name, so a failure does not fail the download.
+    let _ = record_download(&replay.info, &path);
+    Ok((path, replay.data.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_whitespace_and_commas() {
+        assert_eq!(parse_replay_ids("a b,c\td\n\ne,,f"), ["a", "b", "c", "d", "e", "f"]);
+    }
+
+    #[test]
+    fn skips_comments_and_duplicates() {
+        let text = "# exported list\na, b # second\nb\n#c\na d";
+        assert_eq!(parse_replay_ids(text), ["a", "b", "d"]);
+    }
+
+    #[test]
+    fn empty_input_has_no_ids() {
+        assert!(parse_replay_ids("").is_empty());
+        assert!(parse_replay_ids(" ,\n# only a comment\n").is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::tools::replay_processor::Chunk;
 use std::error::Error;
+use std::io::{self, Write};
 
 /// A part of the replay file: either the meta part or a chunk.
 pub enum ReplayPart {
@@ -22,21 +23,28 @@ fn write_string_buffer(s: &str) -> Vec<u8> {
 
 /// Build the final replay buffer.
 pub fn build_replay(parts: &[ReplayPart]) -> Result<Vec<u8>, Box<dyn Error>> {
-    let mut buffers: Vec<u8> = Vec::new();
+    let mut buffer = Vec::new();
+    write_replay(parts, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Write the replay to `writer` part by part, without building the whole file in memory.
+/// Returns the number of bytes written.
+pub fn write_replay<W: Write>(parts: &[ReplayPart], writer: &mut W) -> io::Result<usize> {
+    let mut written = 0;
 
     for part in parts {
         match part {
             ReplayPart::Meta(data) => {
                 // Meta parts are assumed to be already serialized.
-                buffers.extend(data);
+                writer.write_all(data)?;
+                written += data.len();
             }
             ReplayPart::Chunk(chunk) => {
                 let mut body_buffer = Vec::new();
                 match chunk.chunk_type {
                     // Chunk type 0: Header. Write the raw data.
-                    0 => {
-                        body_buffer.extend(&chunk.data);
-                    }
+                    0 => {}
                     // Chunk type 1: Data chunk.
                     1 => {
                         let mut header_buf = [0u8; 16];
@@ -49,7 +57,6 @@ pub fn build_replay(parts: &[ReplayPart]) -> Result<Vec<u8>, Box<dyn Error>> {
                         header_buf[8..12].copy_from_slice(&data_len.to_le_bytes());
                         header_buf[12..16].copy_from_slice(&size_in_bytes.to_le_bytes());
                         body_buffer.extend(&header_buf);
-                        body_buffer.extend(&chunk.data);
                     }
                     // Chunk types 2 and 3: Checkpoint / Event chunks.
                     2 | 3 => {
@@ -68,22 +75,100 @@ pub fn build_replay(parts: &[ReplayPart]) -> Result<Vec<u8>, Box<dyn Error>> {
                         body_buffer.extend(group_buf);
                         body_buffer.extend(meta_buf);
                         body_buffer.extend(&int_buf);
-                        body_buffer.extend(&chunk.data);
                     }
                     other => {
-                        eprintln!("Unknown chunk type encountered: {}", other);
+                        log::warn!("Unknown chunk type encountered: {}", other);
                         continue;
                     }
                 }
-                // Build chunk header (8 bytes): [chunk_type (int32), body length (int32)]
+                // Build chunk header (8 bytes): [chunk_type (int32), body length (int32)].
+                // The chunk data follows the type specific prefix and is written without copying.
                 let mut header_buffer = [0u8; 8];
                 header_buffer[0..4].copy_from_slice(&chunk.chunk_type.to_le_bytes());
-                let body_len = body_buffer.len() as i32;
+                let body_len = (body_buffer.len() + chunk.data.len()) as i32;
                 header_buffer[4..8].copy_from_slice(&body_len.to_le_bytes());
-                buffers.extend(&header_buffer);
-                buffers.extend(&body_buffer);
+                writer.write_all(&header_buffer)?;
+                writer.write_all(&body_buffer)?;
+                writer.write_all(&chunk.data)?;
+                written += header_buffer.len() + body_buffer.len() + chunk.data.len();
             }
         }
     }
-    Ok(buffers)
+    writer.flush()?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(chunk_type: u32, data: &[u8]) -> Chunk {
+        Chunk {
+            data: data.to_vec(),
+            chunk_type,
+            time1: Some(100),
+            time2: Some(200),
+            id: Some("checkpoint0".to_string()),
+            group: Some("checkpoint".to_string()),
+            metadata: None,
+            size_in_bytes: None,
+        }
+    }
+
+    fn parts() -> Vec<ReplayPart> {
+        vec![
+            ReplayPart::Meta(b"META".to_vec()),
+            ReplayPart::Chunk(chunk(0, b"header")),
+            ReplayPart::Chunk(chunk(1, b"stream")),
+            ReplayPart::Chunk(chunk(2, b"checkpoint")),
+            ReplayPart::Chunk(chunk(7, b"ignored")),
+        ]
+    }
+
+    /// The file `parts()` must produce, assembled field by field.
+    fn expected() -> Vec<u8> {
+        let le = |value: i32| value.to_le_bytes().to_vec();
+        [
+            b"META".to_vec(),
+            // Header chunk: type, body length, raw data.
+            le(0), le(6), b"header".to_vec(),
+            // Data chunk: times, data length and size in front of the data.
+            le(1), le(16 + 6), le(100), le(200), le(6), le(6), b"stream".to_vec(),
+            // Checkpoint: id, group and metadata strings with null terminators, times, data.
+            le(2), le(16 + 15 + 5 + 12 + 10),
+            le(12), b"checkpoint0\0".to_vec(),
+            le(11), b"checkpoint\0".to_vec(),
+            le(1), b"\0".to_vec(),
+            le(100), le(200), le(10), b"checkpoint".to_vec(),
+        ]
+        .concat()
+    }
+
+    /// Accepts at most three bytes per call, like a pipe that is slow to drain.
+    struct SmallWrites(Vec<u8>);
+
+    impl Write for SmallWrites {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let len = buf.len().min(3);
+            self.0.extend_from_slice(&buf[..len]);
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn builds_the_replay_layout() {
+        assert_eq!(build_replay(&parts()).unwrap(), expected());
+    }
+
+    #[test]
+    fn streams_the_replay_layout_through_partial_writes() {
+        let mut streamed = SmallWrites(Vec::new());
+        let written = write_replay(&parts(), &mut streamed).unwrap();
+        assert_eq!(streamed.0, expected());
+        assert_eq!(written, expected().len());
+    }
 }
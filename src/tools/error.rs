@@ -0,0 +1,70 @@
+use std::{error::Error, fmt};
+
+use serde::Serialize;
+
+/// Broad cause of a failed replay operation. The CLI maps each kind to its own exit code so
+/// scripts can tell an expired replay from an unreachable server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The replay does not exist or has expired.
+    NotFound,
+    /// The match is still being recorded.
+    NotRecorded,
+    /// The server could not be reached or returned an error status.
+    Network,
+    /// A server response or replay file could not be parsed.
+    Parse,
+    /// The replay could not be written to disk.
+    Write,
+    Other,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReplayError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl ReplayError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+
+    /// Kind of any error returned by the download path. Besides `ReplayError` itself, reqwest
+    /// and serde errors are recognised; anything else is `Other`.
+    pub fn classify(err: &(dyn Error + 'static)) -> ErrorKind {
+        if let Some(err) = err.downcast_ref::<ReplayError>() {
+            err.kind
+        } else if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+            if err.is_decode() {
+                ErrorKind::Parse
+            } else if err.status() == Some(reqwest::StatusCode::NOT_FOUND) {
+                ErrorKind::NotFound
+            } else {
+                ErrorKind::Network
+            }
+        } else if err.is::<serde_json::Error>() {
+            ErrorKind::Parse
+        } else {
+            ErrorKind::Other
+        }
+    }
+
+    pub fn from_boxed(err: Box<dyn Error + Send + Sync>) -> Self {
+        Self::new(Self::classify(err.as_ref()), err.to_string())
+    }
+
+    /// Prefixes the message, keeping the kind.
+    pub fn context(self, context: &str) -> Self {
+        Self::new(self.kind, format!("{}: {}", context, self.message))
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ReplayError {}
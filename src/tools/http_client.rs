@@ -0,0 +1,157 @@
+use reqwest::blocking::Client;
+use reqwest::{Certificate, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::{OnceLock, RwLock},
+    time::Duration,
+};
+
+pub const DEFAULT_USER_AGENT: &str = concat!("PavlovReplayToolbox/", env!("CARGO_PKG_VERSION"));
+
+/// Timeout for lookups the UI is waiting on (listings, avatars, mod.io).
+pub const INTERACTIVE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Options used to build the HTTP client shared by all network code.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    pub connect_timeout_secs: u64,
+    /// Limit for receiving the response headers and for each read of a replay file body, so
+    /// large chunks are not cut off as long as data keeps arriving.
+    pub request_timeout_secs: u64,
+    pub user_agent: String,
+    pub pool_max_idle_per_host: usize,
+    /// Proxy for all traffic, e.g. `http://proxy.corp:3128`. Empty falls back to the
+    /// `HTTP_PROXY`/`HTTPS_PROXY` environment variables.
+    pub proxy_url: String,
+    /// Comma separated hosts that bypass the proxy.
+    pub no_proxy: String,
+    /// PEM or DER certificates trusted in addition to the system roots.
+    pub extra_root_certs: Vec<PathBuf>,
+    /// Skip certificate validation of the replay API, for a local mirror with a self-signed
+    /// certificate. Other hosts are always validated.
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 10,
+            request_timeout_secs: 30,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            pool_max_idle_per_host: 16,
+            proxy_url: String::new(),
+            no_proxy: String::new(),
+            extra_root_certs: Vec::new(),
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+/// The strict client for every host, and the one for the replay API that may accept a
+/// mirror's self-signed certificate.
+#[derive(Clone)]
+struct Clients {
+    default: Client,
+    replay_api: Client,
+}
+
+static CLIENTS: OnceLock<RwLock<Clients>> = OnceLock::new();
+
+fn build_client(settings: &NetworkSettings, accept_invalid_certs: bool) -> Result<Client, Box<dyn Error + Send + Sync>> {
+    let user_agent = if settings.user_agent.trim().is_empty() {
+        DEFAULT_USER_AGENT
+    } else {
+        settings.user_agent.trim()
+    };
+
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(settings.connect_timeout_secs.max(1)))
+        .timeout(Duration::from_secs(settings.request_timeout_secs.max(1)))
+        .user_agent(user_agent)
+        .pool_max_idle_per_host(settings.pool_max_idle_per_host)
+        .danger_accept_invalid_certs(accept_invalid_certs);
+
+    let proxy_url = settings.proxy_url.trim();
+    if !proxy_url.is_empty() {
+        let proxy = Proxy::all(proxy_url)
+            .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy_url, e))?
+            .no_proxy(NoProxy::from_string(&settings.no_proxy));
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &settings.extra_root_certs {
+        for cert in load_certificates(path)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder.build()?)
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>, Box<dyn Error + Send + Sync>> {
+    let data = fs::read(path)
+        .map_err(|e| format!("Failed to read certificate {}: {}", path.display(), e))?;
+
+    let certs = if String::from_utf8_lossy(&data).contains("-----BEGIN") {
+        Certificate::from_pem_bundle(&data)
+    } else {
+        Certificate::from_der(&data).map(|cert| vec![cert])
+    };
+
+    match certs {
+        Ok(certs) if !certs.is_empty() => Ok(certs),
+        Ok(_) => Err(format!("No certificates found in {}", path.display()).into()),
+        Err(e) => Err(format!("Invalid certificate {}: {}", path.display(), e).into()),
+    }
+}
+
+fn build_clients(settings: &NetworkSettings) -> Result<Clients, Box<dyn Error + Send + Sync>> {
+    let default = build_client(settings, false)?;
+    // Without the override both share one connection pool.
+    let replay_api = if settings.accept_invalid_certs {
+        build_client(settings, true)?
+    } else {
+        default.clone()
+    };
+    Ok(Clients { default, replay_api })
+}
+
+/// Rebuilds the shared clients. Clones handed out earlier keep their old configuration.
+pub fn configure(settings: &NetworkSettings) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let clients = build_clients(settings)?;
+    let lock = CLIENTS.get_or_init(|| RwLock::new(clients.clone()));
+    if let Ok(mut current) = lock.write() {
+        *current = clients;
+    }
+    Ok(())
+}
+
+fn with_clients(f: impl FnOnce(&Clients) -> Client) -> Client {
+    let lock = CLIENTS.get_or_init(|| {
+        RwLock::new(build_clients(&NetworkSettings::default()).unwrap_or_else(|_| Clients {
+            default: Client::new(),
+            replay_api: Client::new(),
+        }))
+    });
+    match lock.read() {
+        Ok(clients) => f(&clients),
+        Err(poisoned) => f(&poisoned.into_inner()),
+    }
+}
+
+/// Returns the shared client, which always validates certificates. Cloning is cheap and
+/// reuses the same connection pool.
+pub fn client() -> Client {
+    with_clients(|clients| clients.default.clone())
+}
+
+/// Returns the client for the replay API. It is the only one that honours
+/// `accept_invalid_certs`, so a self-signed mirror does not weaken mod.io, update check or
+/// webhook requests.
+pub fn replay_api_client() -> Client {
+    with_clients(|clients| clients.replay_api.clone())
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::replay_processor::ApiReplay;
+
+/// File name of the library index in the settings directory.
+const LIBRARY_INDEX_FILE: &str = "library.json";
+
+/// Serializes updates of the index from parallel downloads.
+static LIBRARY_INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Guesses the replay ID of a downloaded replay from its file name or contents, for files
+/// missing from the library index.
+///
+/// Default names end in `(<id>).replay`, alternate names (`--alt`) end in ` <id>.replay`.
+/// Files renamed by hand are checked for an `"id":"..."` field near the start.
+pub fn replay_id_from_path(path: &Path) -> Option<String> {
+    let filename = path.file_name()?.to_str()?;
+
+    if let Some(id_start) = filename.rfind('(') {
+        if let Some(id_end) = filename[id_start..].find(')') {
+            return Some(filename[id_start + 1..id_start + id_end].to_string());
+        }
+    }
+
+    let stem = path.file_stem()?.to_str()?;
+    if let Some((_, last)) = stem.rsplit_once(' ') {
+        if !last.is_empty() && last.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Some(last.to_string());
+        }
+    }
+
+    let mut file = fs::File::open(path).ok()?;
+    let mut buffer = [0; 1024];
+    let read = file.read(&mut buffer).ok()?;
+    let content = String::from_utf8_lossy(&buffer[..read]);
+    let id_start = content.find("\"id\":\"")? + 6;
+    let id_end = content[id_start..].find('"')?;
+    Some(content[id_start..id_start + id_end].to_string())
+}
+
+pub fn is_replay_file(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext == "replay")
+}
+
+/// Deepest subfolder level scanned, however many folders a name template adds.
+const MAX_SCAN_DEPTH: usize = 8;
+
+/// All `.replay` files in `dir` and up to `depth` levels of subdirectories, where a name
+/// template may have sorted them into folders. Symbolic links are not followed, so a link
+/// back up the tree cannot make the scan loop.
+pub fn replay_files(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+    while let Some((dir, level)) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // `DirEntry::file_type` describes a link itself rather than its target.
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() && level < depth.min(MAX_SCAN_DEPTH) => {
+                    pending.push((path, level + 1))
+                }
+                Ok(file_type) if file_type.is_file() && is_replay_file(&path) => files.push(path),
+                _ => {}
+            }
+        }
+    }
+    files
+}
+
+/// All replays in `dir` with their IDs, scanning `depth` levels of subdirectories. The
+/// library index knows the ID of every file it recorded, including files a download script
+/// put in deeper folders; other files fall back to [`replay_id_from_path`].
+pub fn downloaded_replays(dir: &Path, depth: usize) -> Vec<(PathBuf, Option<String>)> {
+    let index = LibraryIndex::load();
+    let indexed = index.ids_by_path();
+    let mut replays: Vec<(PathBuf, Option<String>)> = replay_files(dir, depth)
+        .into_iter()
+        .map(|path| {
+            let id = stored_replay_id(&indexed, &path);
+            (path, id)
+        })
+        .collect();
+
+    let listed: HashSet<PathBuf> = replays
+        .iter()
+        .map(|(path, _)| fs::canonicalize(path).unwrap_or_else(|_| path.clone()))
+        .collect();
+    let root = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    for (id, entry) in &index.replays {
+        if entry.path.starts_with(&root) && !listed.contains(&entry.path) && entry.path.is_file() {
+            replays.push((entry.path.clone(), Some(id.clone())));
+        }
+    }
+    replays
+}
+
+fn stored_replay_id(indexed: &HashMap<&Path, &str>, path: &Path) -> Option<String> {
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    indexed
+        .get(canonical.as_path())
+        .map(|id| id.to_string())
+        .or_else(|| replay_id_from_path(path))
+}
+
+/// IDs of all replays already saved in `dir`, scanning `depth` levels of subdirectories.
+pub fn scan_downloaded_ids(dir: &Path, depth: usize) -> HashSet<String> {
+    downloaded_replays(dir, depth).into_iter().filter_map(|(_, id)| id).collect()
+}
+
+/// Directory holding `settings.json` and the library index.
+pub fn settings_dir() -> io::Result<PathBuf> {
+    let path = match directories::ProjectDirs::from("com", "PavlovVR", "ReplayToolbox") {
+        Some(proj_dirs) => proj_dirs.config_dir().to_path_buf(),
+        None => std::env::current_dir()?.join(".config"),
+    };
+    fs::create_dir_all(&path)?;
+    Ok(path)
+}
+
+/// A downloaded replay with the listing entry it was downloaded from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    pub info: ApiReplay,
+    pub path: PathBuf,
+    /// RFC 3339 time of the download.
+    pub downloaded: String,
+}
+
+/// Listing entries of downloaded replays by ID, so the players and maps of local files are
+/// known without parsing them. Replays downloaded before the index existed are missing.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LibraryIndex {
+    pub replays: BTreeMap<String, LibraryEntry>,
+}
+
+impl LibraryIndex {
+    /// Loads the index, or an empty one if it does not exist or cannot be read.
+    pub fn load() -> Self {
+        settings_dir()
+            .and_then(|dir| fs::read_to_string(dir.join(LIBRARY_INDEX_FILE)))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(settings_dir()?.join(LIBRARY_INDEX_FILE), contents)
+    }
+
+    /// Entries whose file still exists.
+    pub fn existing(&self) -> impl Iterator<Item = &LibraryEntry> {
+        self.replays.values().filter(|entry| entry.path.is_file())
+    }
+
+    fn ids_by_path(&self) -> HashMap<&Path, &str> {
+        self.replays
+            .iter()
+            .map(|(id, entry)| (entry.path.as_path(), id.as_str()))
+            .collect()
+    }
+}
+
+/// Adds a downloaded replay to the library index.
+pub fn record_download(info: &ApiReplay, path: &Path) -> io::Result<()> {
+    let _guard = LIBRARY_INDEX_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut index = LibraryIndex::load();
+    index.replays.insert(info.id.clone(), LibraryEntry {
+        info: info.clone(),
+        path: fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+        downloaded: Utc::now().to_rfc3339(),
+    });
+    index.save()
+}
+
+/// Writes a replay, creating the subdirectories a name template may have introduced.
+pub fn write_replay_file(path: &Path, data: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, data)
+}
+
+/// `Map-SND-2024.01.31-20.15.00(abc123)-2.replay` for the second replay named like the first.
+fn numbered_path(path: &Path, number: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}-{}.replay", stem, number))
+}
+
+/// Writes the downloaded replay `replay_id` to `path`. If that file holds a different replay,
+/// for example because a script picked the same name twice, the replay is written next to it
+/// with a `-2`, `-3`, ... suffix instead. Returns the path written.
+pub fn write_downloaded_replay(path: &Path, replay_id: &str, data: &[u8]) -> io::Result<PathBuf> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let index = LibraryIndex::load();
+    let indexed = index.ids_by_path();
+    let mut candidate = path.to_path_buf();
+    let mut number = 1;
+    loop {
+        // Parallel downloads may pick the same name, so the file is claimed with create_new.
+        match fs::OpenOptions::new().write(true).create_new(true).open(&candidate) {
+            Ok(mut file) => {
+                file.write_all(data)?;
+                return Ok(candidate);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                if stored_replay_id(&indexed, &candidate).as_deref() == Some(replay_id) {
+                    fs::write(&candidate, data)?;
+                    return Ok(candidate);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+        number += 1;
+        candidate = numbered_path(path, number);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_ids_from_default_and_alternate_names() {
+        assert_eq!(
+            replay_id_from_path(Path::new("SND/Datacenter-SND-2024.01.31-20.15.00(abc123).replay")).as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            replay_id_from_path(Path::new("2024.01.31-20.15.00 SND Santorini_Island abc123.replay")).as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(replay_id_from_path(Path::new("missing-file.replay")), None);
+    }
+
+    #[test]
+    fn scans_only_the_folders_a_template_adds() {
+        let dir = std::env::temp_dir().join(format!("replaytoolbox-library-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("SND/Datacenter")).unwrap();
+        for file in ["top(a1).replay", "SND/one(b2).replay", "SND/Datacenter/two(c3).replay", "notes.txt"] {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&dir, dir.join("SND/loop")).unwrap();
+
+        let found = |depth| {
+            let mut names: Vec<String> = replay_files(&dir, depth)
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(found(0), ["top(a1).replay"]);
+        assert_eq!(found(1), ["one(b2).replay", "top(a1).replay"]);
+        assert_eq!(found(5), ["one(b2).replay", "top(a1).replay", "two(c3).replay"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn numbered_paths_keep_the_id_guess() {
+        let path = numbered_path(Path::new("SND/Datacenter(abc123).replay"), 2);
+        assert_eq!(path, Path::new("SND/Datacenter(abc123)-2.replay"));
+        assert_eq!(replay_id_from_path(&path).as_deref(), Some("abc123"));
+    }
+}
@@ -0,0 +1,311 @@
+use std::cmp::{Ordering, Reverse};
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tools::address_book::AddressBook;
+use crate::tools::error::{ErrorKind, ReplayError};
+use crate::tools::http_client::{self, INTERACTIVE_TIMEOUT};
+use crate::tools::replay_processor::{parse_created_date, ApiReplay, ApiResponse, ReplayItem, API_BASE_URL};
+
+/// Number of replays the `/find/` endpoint returns per page.
+pub const PAGE_SIZE: usize = 100;
+
+#[derive(Clone, Default)]
+pub struct ReplayFilters {
+    /// Exact game mode name such as `SND`, filtered by the server.
+    pub game_mode: String,
+    pub map_name: String,
+    pub workshop_mods: String,
+    pub platform: PlatformFilter,
+    pub user_id: String,
+    /// `Some(true)` for competitive matches only, `Some(false)` for casual only.
+    pub competitive: Option<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum PlatformFilter {
+    #[default]
+    All,
+    Quest,
+    PC,
+}
+
+impl PlatformFilter {
+    /// Value of the `shack` query parameter for this platform.
+    pub fn shack(&self) -> Option<bool> {
+        match self {
+            PlatformFilter::Quest => Some(true),
+            PlatformFilter::PC => Some(false),
+            PlatformFilter::All => None,
+        }
+    }
+}
+
+pub fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    needle.is_empty() || haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+impl ReplayFilters {
+    /// Case-insensitive substring match on every filter that is set. The user filter also
+    /// matches the nicknames, team tags and notes in `book`. The game mode is compared
+    /// exactly, as the server does.
+    pub fn matches(&self, replay: &ReplayItem, book: &AddressBook) -> bool {
+        (self.game_mode.trim().is_empty() || replay.game_mode.eq_ignore_ascii_case(self.game_mode.trim()))
+            && contains_ignore_case(&replay.map_name, &self.map_name)
+            && contains_ignore_case(&replay.workshop_mods, &self.workshop_mods)
+            && (self.user_id.is_empty()
+                || replay.users.iter().any(|user| book.user_matches(user, &self.user_id)))
+            && self.platform.shack().is_none_or(|shack| replay.shack == shack)
+            && self.competitive.is_none_or(|competitive| replay.competitive == competitive)
+    }
+
+    /// Listing query carrying the filters the `/find/` endpoint applies itself: the game
+    /// mode (`game`) and the platform (`shack`). Everything else is matched locally.
+    pub fn query(&self, offset: usize, live: bool) -> ListQuery {
+        let game_mode = self.game_mode.trim();
+        ListQuery {
+            offset,
+            live,
+            // Mode names are upper case on the server.
+            game: (!game_mode.is_empty()).then(|| game_mode.to_uppercase()),
+            shack: self.platform.shack(),
+        }
+    }
+
+    /// Whether any filter is applied locally, in which case a single page can miss matches
+    /// that exist elsewhere in the catalogue.
+    pub fn has_local_filters(&self) -> bool {
+        !self.map_name.is_empty()
+            || !self.workshop_mods.is_empty()
+            || !self.user_id.is_empty()
+            || self.competitive.is_some()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// Order returned by the server.
+    #[default]
+    Server,
+    Date,
+    TimeSince,
+    GameMode,
+    Map,
+    Players,
+    ModCount,
+    Competitive,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 8] = [
+        SortKey::Server, SortKey::Date, SortKey::TimeSince, SortKey::GameMode,
+        SortKey::Map, SortKey::Players, SortKey::ModCount, SortKey::Competitive,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortKey::Server => "Server order",
+            SortKey::Date => "Date",
+            SortKey::TimeSince => "Time since",
+            SortKey::GameMode => "Game mode",
+            SortKey::Map => "Map",
+            SortKey::Players => "Players",
+            SortKey::ModCount => "Mod count",
+            SortKey::Competitive => "Competitive",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplaySort {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl ReplaySort {
+    fn compare(&self, a: &ReplayItem, b: &ReplayItem) -> Ordering {
+        match self.key {
+            SortKey::Server | SortKey::Date => Ordering::Equal,
+            SortKey::TimeSince => a.time_since.cmp(&b.time_since),
+            SortKey::GameMode => a.game_mode.to_lowercase().cmp(&b.game_mode.to_lowercase()),
+            SortKey::Map => a.map_name.to_lowercase().cmp(&b.map_name.to_lowercase()),
+            SortKey::Players => a.users.len().cmp(&b.users.len()),
+            SortKey::ModCount => a.modcount.cmp(&b.modcount),
+            SortKey::Competitive => a.competitive.cmp(&b.competitive),
+        }
+    }
+
+    /// Stable sort, so replays with equal keys keep the server order.
+    pub fn apply(&self, replays: &mut [ReplayItem]) {
+        match self.key {
+            SortKey::Server => return,
+            // Dates are parsed once per replay rather than once per comparison.
+            SortKey::Date => {
+                let date = |replay: &ReplayItem| parse_created_date(&replay.created_date).ok();
+                if self.descending {
+                    replays.sort_by_cached_key(|replay| Reverse(date(replay)));
+                } else {
+                    replays.sort_by_cached_key(date);
+                }
+                return;
+            }
+            _ => {}
+        }
+        replays.sort_by(|a, b| {
+            let ordering = self.compare(a, b);
+            if self.descending { ordering.reverse() } else { ordering }
+        });
+    }
+}
+
+/// Query parameters understood by the `/find/` endpoint.
+#[derive(Clone, Debug, Default)]
+pub struct ListQuery {
+    pub offset: usize,
+    pub live: bool,
+    /// Game mode such as `SND`, `None` for all modes.
+    pub game: Option<String>,
+    /// `Some(true)` for Quest (shack) replays only, `Some(false)` for PC only.
+    pub shack: Option<bool>,
+}
+
+impl ListQuery {
+    pub fn url(&self) -> String {
+        let mut url = reqwest::Url::parse(&format!("{}/find/", API_BASE_URL)).expect("API_BASE_URL is a valid URL");
+        url.query_pairs_mut()
+            .append_pair("game", self.game.as_deref().unwrap_or("all"))
+            .append_pair("offset", &self.offset.to_string())
+            .append_pair("live", &self.live.to_string());
+        if let Some(shack) = self.shack {
+            url.query_pairs_mut().append_pair("shack", &shack.to_string());
+        }
+        url.into()
+    }
+}
+
+pub fn fetch_page(query: &ListQuery) -> Result<ApiResponse, Box<dyn Error + Send + Sync>> {
+    let response = match http_client::replay_api_client().get(query.url()).timeout(INTERACTIVE_TIMEOUT).send() {
+        Ok(resp) => {
+            if !resp.status().is_success() {
+                return Err(ReplayError::new(ErrorKind::Network, format!("Server returned error status: {} - {}",
+                    resp.status().as_u16(),
+                    resp.status().canonical_reason().unwrap_or("Unknown error"))).into());
+            }
+            resp
+        },
+        Err(e) => {
+            let message = if e.is_timeout() {
+                "Connection timed out. Server may be down or unreachable.".to_string()
+            } else if e.is_connect() {
+                "Failed to connect to server. Please check your internet connection.".to_string()
+            } else {
+                format!("Network error: {}", e)
+            };
+            return Err(ReplayError::new(ErrorKind::Network, message).into());
+        }
+    };
+
+    response.json::<ApiResponse>().map_err(|e| {
+        ReplayError::new(ErrorKind::Parse, format!(
+            "Failed to parse server response: {}. The API may have changed format.", e
+        )).into()
+    })
+}
+
+/// Walks every page of the listing, calling `on_page` with each page's replays, the number
+/// of entries scanned so far and the total reported by the server. Stops early when
+/// `on_page` returns `false`.
+pub fn for_each_page(
+    query: &ListQuery,
+    mut on_page: impl FnMut(Vec<ApiReplay>, usize, i32) -> bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut query = query.clone();
+    loop {
+        let page = fetch_page(&query)?;
+        let page_len = page.replays.len();
+        query.offset += PAGE_SIZE;
+        if !on_page(page.replays, query.offset, page.total) {
+            return Ok(());
+        }
+        if page_len == 0 || query.offset >= page.total.max(0) as usize {
+            return Ok(());
+        }
+    }
+}
+
+/// Walks the whole catalogue with the server-side part of `filters` and calls `on_matches`
+/// with the replays of each page that match the rest, the number of entries scanned and the
+/// total. Stops early when `on_matches` returns `false`.
+pub fn search_all_pages(
+    filters: &ReplayFilters,
+    book: &AddressBook,
+    live: bool,
+    mut on_matches: impl FnMut(Vec<ApiReplay>, usize, i32) -> bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    for_each_page(&filters.query(0, live), |replays, scanned, total| {
+        let matches = replays
+            .into_iter()
+            .filter(|replay| filters.matches(&ReplayItem::from(replay.clone()), book))
+            .collect();
+        on_matches(matches, scanned, total)
+    })
+}
+
+pub fn total_pages(total: i32) -> usize {
+    (total.max(0) as usize).div_ceil(PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::replay_processor::test_replays::replay_item;
+
+    fn replay(id: &str, map_name: &str, created_date: &str, users: &[&str]) -> ReplayItem {
+        ReplayItem {
+            map_name: map_name.to_string(),
+            created_date: created_date.to_string(),
+            ..replay_item(id, users)
+        }
+    }
+
+    fn replays() -> Vec<ReplayItem> {
+        vec![
+            replay("a", "datacenter", "2024-01-31T20:15:00.000Z", &["1", "2", "3", "4"]),
+            replay("b", "Santorini", "2024-02-01T10:00:00.000Z", &["1", "2", "3", "4", "5", "6"]),
+            replay("c", "Datacenter", "2023-12-24T18:00:00.000Z", &["1", "2", "3", "4"]),
+            replay("d", "Bridge", "not a date", &["1", "2"]),
+        ]
+    }
+
+    fn sorted(key: SortKey, descending: bool) -> Vec<String> {
+        let mut replays = replays();
+        ReplaySort { key, descending }.apply(&mut replays);
+        replays.into_iter().map(|replay| replay.id).collect()
+    }
+
+    #[test]
+    fn server_order_is_kept() {
+        assert_eq!(sorted(SortKey::Server, false), ["a", "b", "c", "d"]);
+        assert_eq!(sorted(SortKey::Server, true), ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn sorts_by_parsed_date_with_invalid_dates_first() {
+        assert_eq!(sorted(SortKey::Date, false), ["d", "c", "a", "b"]);
+        assert_eq!(sorted(SortKey::Date, true), ["b", "a", "c", "d"]);
+    }
+
+    #[test]
+    fn sorts_maps_ignoring_case_and_keeps_ties_stable() {
+        assert_eq!(sorted(SortKey::Map, false), ["d", "a", "c", "b"]);
+        assert_eq!(sorted(SortKey::Map, true), ["b", "a", "c", "d"]);
+    }
+
+    #[test]
+    fn sorts_by_player_count() {
+        assert_eq!(sorted(SortKey::Players, true), ["b", "a", "c", "d"]);
+    }
+}
@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::time::Duration;
+
+use chrono::{TimeDelta, Utc};
+
+use crate::tools::listing::{for_each_page, ListQuery};
+use crate::tools::replay_processor::{parse_created_date, replay_state, ApiReplay};
+
+/// How often the Live tab reloads the listing while it is open or a match is awaited.
+pub const LIVE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// Awaited matches are dropped if they have not finished recording after this long.
+pub const LIVE_WAIT_LIMIT: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// Every match currently being recorded.
+pub fn fetch_live_replays() -> Result<Vec<ApiReplay>, Box<dyn Error + Send + Sync>> {
+    let mut live = Vec::new();
+    for_each_page(&ListQuery { live: true, ..Default::default() }, |replays, _, _| {
+        live.extend(replays);
+        true
+    })?;
+    Ok(live)
+}
+
+/// Whether the recording of `replay_id` has finished and can be downloaded.
+pub fn is_recorded(replay_id: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    Ok(replay_state(replay_id)? == "Recorded")
+}
+
+/// Time since the match started. `None` if the date cannot be parsed.
+pub fn elapsed_since(created: &str) -> Option<TimeDelta> {
+    parse_created_date(created).ok().map(|created| Utc::now() - created.to_utc())
+}
+
+/// Short duration such as `1h 12m` or `8m 30s`.
+pub fn elapsed_text(elapsed: TimeDelta) -> String {
+    let elapsed = elapsed.max(TimeDelta::zero());
+    let (hours, minutes, seconds) = (elapsed.num_hours(), elapsed.num_minutes() % 60, elapsed.num_seconds() % 60);
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, seconds)
+    }
+}
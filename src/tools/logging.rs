@@ -0,0 +1,335 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock, RwLock};
+
+use chrono::{DateTime, Local};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::tools::library::settings_dir;
+
+/// Default filter: informational messages of the toolbox, warnings of its dependencies.
+pub const DEFAULT_LOG_FILTER: &str = "info";
+const LOG_FILE_NAME: &str = "replaytoolbox.log";
+/// The log file is rotated once it grows past this size.
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the current one (`replaytoolbox.1.log`, ...).
+const ROTATED_LOG_FILES: usize = 3;
+/// Records kept in memory for the log viewer.
+const RECENT_RECORDS: usize = 2000;
+const CRATE_TARGET: &str = env!("CARGO_CRATE_NAME");
+
+/// Levels per log target, written like `info` or `warn,tools::batch=debug`.
+///
+/// The first bare level applies to the toolbox itself; dependencies log at most warnings
+/// unless a directive names them. Targets match a module and its submodules, with or
+/// without the crate name in front.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFilter {
+    default: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self { default: LevelFilter::Info, targets: Vec::new() }
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level.trim()).map_err(|_| {
+        format!("Invalid log level '{}', expected off, error, warn, info, debug or trace", level.trim())
+    })
+}
+
+impl FromStr for LogFilter {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut filter = LogFilter::default();
+        for directive in spec.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => filter.targets.push((target.trim().to_string(), parse_level(level)?)),
+                None => filter.default = parse_level(directive)?,
+            }
+        }
+        // Longer targets are more specific and win.
+        filter.targets.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(filter)
+    }
+}
+
+fn target_matches(target: &str, directive: &str) -> bool {
+    let within = |target: &str| {
+        target == directive || target.strip_prefix(directive).is_some_and(|rest| rest.starts_with("::"))
+    };
+    within(target)
+        || target
+            .strip_prefix(CRATE_TARGET)
+            .and_then(|rest| rest.strip_prefix("::"))
+            .is_some_and(within)
+}
+
+impl LogFilter {
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        if let Some((_, level)) = self.targets.iter().find(|(directive, _)| target_matches(target, directive)) {
+            return *level;
+        }
+        if target == CRATE_TARGET || target.starts_with(&format!("{}::", CRATE_TARGET)) {
+            self.default
+        } else {
+            self.default.min(LevelFilter::Warn)
+        }
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.targets.iter().map(|(_, level)| *level).fold(self.default, Ord::max)
+    }
+}
+
+/// A message kept for the log viewer.
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl LogRecord {
+    pub fn line(&self) -> String {
+        format!("{} {:5} [{}] {}", self.time.format("%Y-%m-%d %H:%M:%S%.3f"), self.level, self.target, self.message)
+    }
+}
+
+/// Log file that is moved aside to `replaytoolbox.1.log` once it gets too large.
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    path.with_extension(format!("{}.log", index))
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size > MAX_LOG_SIZE && self.rotate().is_err() {
+            // Keep writing to the current file and try again once it grew further.
+            self.size = 0;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// GUI and command line processes share the file. The lock keeps two of them from
+    /// rotating at once; a process that finds the file already rotated only reopens it.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("log.lock"))?;
+        lock.lock()?;
+        if fs::metadata(&self.path).map(|metadata| metadata.len()).unwrap_or(0) > MAX_LOG_SIZE {
+            for index in (1..ROTATED_LOG_FILES).rev() {
+                let from = rotated_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(&from, rotated_path(&self.path, index + 1))?;
+                }
+            }
+            fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+        *self = Self::open(self.path.clone())?;
+        Ok(())
+    }
+}
+
+struct Logger {
+    filter: RwLock<LogFilter>,
+    file: Mutex<Option<RotatingFile>>,
+    recent: Mutex<VecDeque<LogRecord>>,
+    /// Also print records to stderr, for the command line.
+    stderr: bool,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter
+            .read()
+            .is_ok_and(|filter| metadata.level() <= filter.level_for(metadata.target()))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let record = LogRecord {
+            time: Local::now(),
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        let line = record.line();
+
+        if self.stderr {
+            eprintln!("{}", line);
+        }
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.write_line(&line);
+            }
+        }
+        if let Ok(mut recent) = self.recent.lock() {
+            if recent.len() >= RECENT_RECORDS {
+                recent.pop_front();
+            }
+            recent.push_back(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Path of the current log file.
+pub fn log_file_path() -> Option<PathBuf> {
+    settings_dir().ok().map(|dir| dir.join(LOG_FILE_NAME))
+}
+
+/// Installs the logger. Records go to the log file in the settings directory and, with
+/// `stderr`, to the terminal. Returns an error if the log file cannot be opened; logging to
+/// memory and stderr still works in that case.
+pub fn init(filter: LogFilter, stderr: bool) -> Result<(), String> {
+    let file = log_file_path()
+        .ok_or_else(|| "No settings directory for the log file".to_string())
+        .and_then(|path| {
+            RotatingFile::open(path.clone()).map_err(|e| format!("Failed to open log file {}: {}", path.display(), e))
+        });
+    let (file, result) = match file {
+        Ok(file) => (Some(file), Ok(())),
+        Err(e) => (None, Err(e)),
+    };
+
+    log::set_max_level(filter.max_level());
+    let logger = LOGGER.get_or_init(|| Logger {
+        filter: RwLock::new(filter),
+        file: Mutex::new(file),
+        recent: Mutex::new(VecDeque::new()),
+        stderr,
+    });
+    log::set_logger(logger).map_err(|e| e.to_string())?;
+    result
+}
+
+/// Replaces the filter of the installed logger.
+pub fn set_filter(filter: LogFilter) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    log::set_max_level(filter.max_level());
+    if let Ok(mut current) = logger.filter.write() {
+        *current = filter;
+    }
+}
+
+/// Records kept in memory, oldest first.
+pub fn recent_records() -> Vec<LogRecord> {
+    LOGGER
+        .get()
+        .and_then(|logger| logger.recent.lock().ok().map(|recent| recent.iter().cloned().collect()))
+        .unwrap_or_default()
+}
+
+pub fn clear_recent_records() {
+    if let Some(logger) = LOGGER.get() {
+        if let Ok(mut recent) = logger.recent.lock() {
+            recent.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(spec: &str, target: &str) -> LevelFilter {
+        spec.parse::<LogFilter>().unwrap().level_for(target)
+    }
+
+    #[test]
+    fn bare_level_applies_to_the_toolbox() {
+        let target = format!("{}::tools::batch", CRATE_TARGET);
+        assert_eq!(level("debug", &target), LevelFilter::Debug);
+        assert_eq!(level("debug", CRATE_TARGET), LevelFilter::Debug);
+        assert_eq!(level("", &target), LevelFilter::Info);
+        assert_eq!(level(" Trace ", &target), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn dependencies_log_warnings_unless_named() {
+        assert_eq!(level("trace", "reqwest::connect"), LevelFilter::Warn);
+        assert_eq!(level("error", "reqwest::connect"), LevelFilter::Error);
+        assert_eq!(level("info,reqwest=debug", "reqwest::connect"), LevelFilter::Debug);
+        assert_eq!(level("info,reqwest=debug", "reqwest_extra"), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn most_specific_target_wins() {
+        let spec = "warn,tools=info,tools::batch=debug";
+        assert_eq!(level(spec, &format!("{}::tools::batch", CRATE_TARGET)), LevelFilter::Debug);
+        assert_eq!(level(spec, &format!("{}::tools::library", CRATE_TARGET)), LevelFilter::Info);
+        assert_eq!(level(spec, &format!("{}::app", CRATE_TARGET)), LevelFilter::Warn);
+        assert_eq!(level(&format!("off,{}::app=trace", CRATE_TARGET), &format!("{}::app", CRATE_TARGET)), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn max_level_covers_every_directive() {
+        assert_eq!("warn,tools::batch=trace".parse::<LogFilter>().unwrap().max_level(), LevelFilter::Trace);
+        assert_eq!("off".parse::<LogFilter>().unwrap().max_level(), LevelFilter::Off);
+    }
+
+    #[test]
+    fn rejects_unknown_levels() {
+        assert!("verbose".parse::<LogFilter>().is_err());
+        assert!("info,tools=loud".parse::<LogFilter>().is_err());
+    }
+
+    #[test]
+    fn only_one_process_rotates_a_shared_file() {
+        let dir = std::env::temp_dir().join(format!("replaytoolbox-log-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LOG_FILE_NAME);
+
+        let mut gui = RotatingFile::open(path.clone()).unwrap();
+        gui.file.set_len(MAX_LOG_SIZE + 1).unwrap();
+        let mut cli = RotatingFile::open(path.clone()).unwrap();
+        gui.size = cli.size;
+
+        gui.write_line("from the gui").unwrap();
+        // The command line process still has the rotated file open and only reopens.
+        cli.write_line("from the cli").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "from the gui\nfrom the cli\n");
+        assert_eq!(fs::metadata(rotated_path(&path, 1)).unwrap().len(), MAX_LOG_SIZE + 1);
+        assert!(!rotated_path(&path, 2).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,19 @@
 pub mod replay_buffer;
+pub mod address_book;
+pub mod auto_download;
 pub mod build_meta;
+pub mod batch;
 pub mod build_replay;
-pub mod replay_processor;
\ No newline at end of file
+pub mod error;
+pub mod http_client;
+pub mod library;
+pub mod live;
+pub mod listing;
+pub mod logging;
+pub mod naming;
+pub mod players;
+pub mod replay_processor;
+pub mod replay_reader;
+pub mod script;
+pub mod tracking;
+pub mod watchlist;
@@ -0,0 +1,434 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::tools::replay_processor::{parse_created_date, ApiReplay, MetaData};
+
+/// `Datacenter-SND-2024.01.31-20.15.00(abc123).replay`
+pub const DEFAULT_TEMPLATE: &str = "{map}-{mode}-{date}({id})";
+/// Timestamp first so file browsers sort replays chronologically (`--alt`).
+pub const ALTERNATE_TEMPLATE: &str = "{date} {mode} {map} {id}";
+pub const DEFAULT_DATE_FORMAT: &str = "%Y.%m.%d-%H.%M.%S";
+pub const ISO8601_DATE_FORMAT: &str = "%+";
+
+pub const PLACEHOLDERS: [&str; 7] = ["map", "mode", "date", "id", "competitive", "platform", "players"];
+
+/// Characters replaced in placeholder values so a map name cannot add separators or subdirectories.
+const UNSAFE_NAME_CHARS: [char; 12] = [' ', '<', '>', ':', '"', '/', ',', '\\', '?', '*', '=', '|'];
+/// Characters that are invalid in file names on at least one supported platform.
+const INVALID_PATH_CHARS: [char; 7] = ['<', '>', ':', '"', '|', '?', '*'];
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+/// Longest file or directory name written, in bytes. Most file systems allow 255, this
+/// leaves room for the extension and for tools that append suffixes.
+const MAX_COMPONENT_LEN: usize = 200;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NamingOptions {
+    /// File name template without the `.replay` extension. `/` separates subdirectories.
+    pub template: String,
+    /// Format `{date}` in UTC instead of the offset reported by the server.
+    pub utc: bool,
+    /// Replaces spaces and separators in placeholder values. The date first preset uses `_`
+    /// so the words of a map name stay apart from the space separated fields.
+    pub replacement: char,
+}
+
+impl Default for NamingOptions {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_TEMPLATE.to_string(),
+            utc: false,
+            replacement: '-',
+        }
+    }
+}
+
+impl NamingOptions {
+    /// Options equivalent to the `--alt` and `--iso8601` switches.
+    pub fn preset(alternate: bool, iso8601: bool, utc: bool) -> Self {
+        let template = if alternate { ALTERNATE_TEMPLATE } else { DEFAULT_TEMPLATE };
+        let template = if iso8601 {
+            template.replace("{date}", &format!("{{date:{}}}", ISO8601_DATE_FORMAT))
+        } else {
+            template.to_string()
+        };
+        let replacement = if alternate { '_' } else { '-' };
+        Self { template, utc, replacement }
+    }
+
+    /// Number of subfolder levels the template sorts replays into.
+    pub fn folder_depth(&self) -> usize {
+        self.template.matches(['/', '\\']).count()
+    }
+
+    /// Checks that the template parses and contains `{id}`. Without the ID, different replays
+    /// of the same map and time get the same name.
+    pub fn validate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if NameTemplate::parse(&self.template)?.has_id() {
+            Ok(())
+        } else {
+            Err("Name template must contain {id} so every replay gets its own file name".into())
+        }
+    }
+}
+
+/// Values available to a name template. Fields that are unknown for local replays are `None`.
+pub struct NameFields<'a> {
+    pub map: &'a str,
+    pub mode: &'a str,
+    pub created: DateTime<FixedOffset>,
+    pub id: Option<&'a str>,
+    pub competitive: bool,
+    pub shack: Option<bool>,
+    pub players: Option<usize>,
+}
+
+impl<'a> NameFields<'a> {
+    pub fn from_meta(meta: &'a MetaData) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let created = parse_created_date(&meta.created)
+            .map_err(|e| format!("Failed to parse replay date: {}", e))?;
+        Ok(Self {
+            map: &meta.friendly_name,
+            mode: &meta.game_mode,
+            created,
+            id: None,
+            competitive: meta.competitive,
+            shack: None,
+            players: None,
+        })
+    }
+}
+
+enum Segment {
+    Text(String),
+    Field { name: String, format: Option<String> },
+}
+
+pub struct NameTemplate {
+    segments: Vec<Segment>,
+}
+
+impl NameTemplate {
+    /// Parses `{placeholder}` and `{date:FORMAT}` fields. `{{` and `}}` are literal braces.
+    pub fn parse(template: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => return Err("Unmatched '}' in name template. Use '}}' for a literal brace.".into()),
+                '{' => {
+                    let mut field = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => field.push(c),
+                            None => return Err("Unclosed '{' in name template".into()),
+                        }
+                    }
+
+                    let (name, format) = match field.split_once(':') {
+                        Some((name, format)) => (name.trim(), Some(format.to_string())),
+                        None => (field.trim(), None),
+                    };
+                    if !PLACEHOLDERS.contains(&name) {
+                        return Err(format!(
+                            "Unknown placeholder '{{{}}}' in name template. Available: {}",
+                            name, PLACEHOLDERS.join(", ")
+                        ).into());
+                    }
+                    if let Some(format) = &format {
+                        if name != "date" {
+                            return Err(format!("Placeholder '{{{}}}' does not take a format", name).into());
+                        }
+                        if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                            return Err(format!("Invalid date format '{}' in name template", format).into());
+                        }
+                    }
+
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Field { name: name.to_string(), format });
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn has_id(&self) -> bool {
+        self.segments.iter().any(|segment| matches!(segment, Segment::Field { name, .. } if name == "id"))
+    }
+
+    fn field_value(name: &str, format: Option<&str>, fields: &NameFields, options: &NamingOptions) -> String {
+        let replacement = options.replacement.to_string();
+        match name {
+            "map" => fields.map.replace(UNSAFE_NAME_CHARS, &replacement),
+            "mode" => fields.mode.replace(UNSAFE_NAME_CHARS, &replacement),
+            "date" => {
+                let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+                if options.utc {
+                    fields.created.to_utc().format(format).to_string()
+                } else {
+                    fields.created.format(format).to_string()
+                }
+            }
+            "id" => fields.id.unwrap_or_default().replace(UNSAFE_NAME_CHARS, &replacement),
+            "competitive" => if fields.competitive { "competitive" } else { "casual" }.to_string(),
+            "platform" => match fields.shack {
+                Some(true) => "quest",
+                Some(false) => "pc",
+                None => "unknown",
+            }.to_string(),
+            "players" => fields.players.map_or_else(|| "unknown".to_string(), |players| players.to_string()),
+            _ => String::new(),
+        }
+    }
+
+    /// Renders the template to a relative path ending in `.replay`. Every path component is
+    /// sanitized, so the result is valid on Windows, macOS and Linux.
+    pub fn render(&self, fields: &NameFields, options: &NamingOptions) -> PathBuf {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => rendered.push_str(text),
+                Segment::Field { name, format } => {
+                    rendered.push_str(&Self::field_value(name, format.as_deref(), fields, options))
+                }
+            }
+        }
+
+        sanitize_relative_path(&rendered)
+    }
+}
+
+/// Turns a name with `/` or `\\` separated subdirectories into a relative path ending in
+/// `.replay`, sanitizing every component.
+pub fn sanitize_relative_path(name: &str) -> PathBuf {
+    let name = name.strip_suffix(".replay").unwrap_or(name);
+    let mut components: Vec<String> = name
+        .split(['/', '\\'])
+        .filter(|component| !component.trim().is_empty())
+        .map(sanitize_component)
+        .collect();
+    if components.is_empty() {
+        components.push("_".to_string());
+    }
+    if let Some(file_name) = components.last_mut() {
+        file_name.push_str(".replay");
+    }
+    components.iter().collect()
+}
+
+/// Makes a single file or directory name safe on every platform: replaces invalid and control
+/// characters, trims trailing dots and spaces, avoids Windows reserved device names and
+/// limits the length.
+pub fn sanitize_component(component: &str) -> String {
+    let mut name: String = component
+        .chars()
+        .map(|c| if c.is_control() || INVALID_PATH_CHARS.contains(&c) { '-' } else { c })
+        .collect();
+
+    if name.len() > MAX_COMPONENT_LEN {
+        let mut end = MAX_COMPONENT_LEN;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name.truncate(end);
+    }
+
+    let mut name = name.trim().trim_end_matches(['.', ' ']).to_string();
+    if name.is_empty() {
+        name.push('_');
+    }
+
+    let stem = name.split('.').next().unwrap_or_default().to_uppercase();
+    if WINDOWS_RESERVED_NAMES.contains(&stem.as_str()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+pub fn render_filename(fields: &NameFields, options: &NamingOptions) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    Ok(NameTemplate::parse(&options.template)?.render(fields, options))
+}
+
+/// Relative path for a downloaded replay, e.g. `Datacenter-SND-2024.01.31-20.15.00(abc123).replay`.
+pub fn replay_filename(
+    meta: &MetaData,
+    info: &ApiReplay,
+    options: &NamingOptions,
+) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    let fields = NameFields {
+        id: Some(&info.id),
+        shack: Some(info.shack),
+        players: info.users.as_ref().map(Vec::len),
+        ..NameFields::from_meta(meta)?
+    };
+    render_filename(&fields, options)
+}
+
+/// Stands in for the replay ID of a replay processed from local chunks: `local-` and a hash
+/// of the replay `data`, so two local replays of the same match time get different names.
+pub fn local_replay_id(data: &[u8]) -> String {
+    // FNV-1a, which unlike the standard library hasher is the same in every build.
+    let hash = data
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+    format!("local-{:016x}", hash)
+}
+
+/// Relative path for a replay processed from local chunks, which have no platform or player
+/// list. `{id}` is [`local_replay_id`] of the built replay `data`.
+pub fn local_replay_filename(
+    meta: &MetaData,
+    data: &[u8],
+    options: &NamingOptions,
+) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    let id = local_replay_id(data);
+    render_filename(&NameFields { id: Some(&id), ..NameFields::from_meta(meta)? }, options)
+}
+
+/// Example output of `options`, shown while editing the template.
+pub fn preview_filename(options: &NamingOptions) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    let fields = NameFields {
+        map: "Datacenter",
+        mode: "SND",
+        created: parse_created_date("2024-01-31T20:15:00.000Z")?,
+        id: Some("65ba9a2c8f1e4b0012345678"),
+        competitive: true,
+        shack: Some(false),
+        players: Some(10),
+    };
+    render_filename(&fields, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(map: &str) -> NameFields<'_> {
+        NameFields {
+            map,
+            mode: "SND",
+            created: parse_created_date("2024-01-31T20:15:00.000+01:00").unwrap(),
+            id: Some("abc123"),
+            competitive: false,
+            shack: Some(true),
+            players: Some(8),
+        }
+    }
+
+    fn render(template: &str, fields: &NameFields) -> String {
+        let options = NamingOptions { template: template.to_string(), ..Default::default() };
+        render_filename(fields, &options).unwrap().to_string_lossy().replace('\\', "/")
+    }
+
+    #[test]
+    fn renders_default_template() {
+        assert_eq!(render(DEFAULT_TEMPLATE, &fields("Datacenter")), "Datacenter-SND-2024.01.31-20.15.00(abc123).replay");
+    }
+
+    #[test]
+    fn renders_placeholders_formats_and_subdirectories() {
+        assert_eq!(
+            render("{mode}/{map}/{date:%Y-%m-%d} {competitive} {platform} {players} {id}", &fields("Datacenter")),
+            "SND/Datacenter/2024-01-31 casual quest 8 abc123.replay"
+        );
+        assert_eq!(render("{{{id}}}", &fields("Datacenter")), "{abc123}.replay");
+    }
+
+    #[test]
+    fn formats_date_in_utc() {
+        let options = NamingOptions { template: "{date}".to_string(), utc: true, ..Default::default() };
+        assert_eq!(render_filename(&fields("Datacenter"), &options).unwrap(), PathBuf::from("2024.01.31-19.15.00.replay"));
+    }
+
+    #[test]
+    fn placeholder_values_cannot_add_directories() {
+        assert_eq!(render("{map} {id}", &fields("Santorini/Island: Night")), "Santorini-Island--Night abc123.replay");
+    }
+
+    #[test]
+    fn alternate_preset_keeps_underscores_in_map_names() {
+        let options = NamingOptions::preset(true, false, false);
+        assert_eq!(
+            render_filename(&fields("Santorini Island"), &options).unwrap(),
+            PathBuf::from("2024.01.31-20.15.00 SND Santorini_Island abc123.replay")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        for template in ["{map", "{map}}", "{unknown}", "{map:%Y}", "{date:%Q}"] {
+            assert!(NameTemplate::parse(template).is_err(), "{}", template);
+        }
+    }
+
+    #[test]
+    fn local_ids_depend_on_the_replay_data() {
+        assert_eq!(local_replay_id(b""), "local-cbf29ce484222325");
+        assert_eq!(local_replay_id(b"replay"), local_replay_id(b"replay"));
+        assert_ne!(local_replay_id(b"replay 1"), local_replay_id(b"replay 2"));
+    }
+
+    #[test]
+    fn validation_requires_id() {
+        let options = |template: &str| NamingOptions { template: template.to_string(), ..Default::default() };
+        assert!(options(DEFAULT_TEMPLATE).validate().is_ok());
+        assert!(options(ALTERNATE_TEMPLATE).validate().is_ok());
+        assert!(options("{map}-{date}").validate().is_err());
+        assert!(options("{{id}}").validate().is_err());
+    }
+
+    #[test]
+    fn sanitizes_invalid_characters_and_trailing_dots() {
+        assert_eq!(sanitize_component("a<b>c:d\"e|f?g*h"), "a-b-c-d-e-f-g-h");
+        assert_eq!(sanitize_component("tab\there"), "tab-here");
+        assert_eq!(sanitize_component("name. . "), "name");
+        assert_eq!(sanitize_component(" ... "), "_");
+    }
+
+    #[test]
+    fn avoids_windows_reserved_names() {
+        assert_eq!(sanitize_component("CON"), "_CON");
+        assert_eq!(sanitize_component("nul.replay"), "_nul.replay");
+        assert_eq!(sanitize_component("Com1"), "_Com1");
+        assert_eq!(sanitize_component("CONSOLE"), "CONSOLE");
+    }
+
+    #[test]
+    fn limits_component_length_on_char_boundaries() {
+        let name = sanitize_component(&"é".repeat(150));
+        assert!(name.len() <= MAX_COMPONENT_LEN);
+        assert_eq!(name, "é".repeat(MAX_COMPONENT_LEN / 2));
+    }
+
+    #[test]
+    fn relative_paths_drop_empty_components() {
+        assert_eq!(sanitize_relative_path("/SND//Datacenter/ /x.replay"), ["SND", "Datacenter", "x.replay"].iter().collect::<PathBuf>());
+        assert_eq!(sanitize_relative_path(""), PathBuf::from("_.replay"));
+    }
+}
@@ -0,0 +1,117 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::tools::address_book::AddressBook;
+use crate::tools::library::LibraryIndex;
+use crate::tools::replay_processor::{parse_created_date, ReplayItem};
+
+/// A replay a player appears in.
+#[derive(Clone, Debug)]
+pub struct PlayerReplay {
+    pub replay: ReplayItem,
+    /// Downloaded file, if the replay is in the local library.
+    pub local_path: Option<PathBuf>,
+}
+
+/// Everything known about one user ID from fetched listings and the local library.
+#[derive(Clone, Debug)]
+pub struct PlayerStats {
+    pub user_id: String,
+    /// Maps and game modes with the number of matches on each, most played first.
+    pub maps: Vec<(String, usize)>,
+    pub modes: Vec<(String, usize)>,
+    pub last_seen: Option<DateTime<FixedOffset>>,
+    /// Newest first.
+    pub replays: Vec<PlayerReplay>,
+}
+
+impl PlayerStats {
+    pub fn matches(&self) -> usize {
+        self.replays.len()
+    }
+
+    pub fn local_matches(&self) -> usize {
+        self.replays.iter().filter(|replay| replay.local_path.is_some()).count()
+    }
+}
+
+fn by_count(counts: BTreeMap<String, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by_key(|(_, count)| Reverse(*count));
+    counts
+}
+
+/// Aggregates the players of `listed` replays and of the replays in `library`. A replay in
+/// both counts once.
+pub fn aggregate_players<'a>(listed: impl IntoIterator<Item = &'a ReplayItem>, library: &LibraryIndex) -> Vec<PlayerStats> {
+    let mut replays: HashMap<String, PlayerReplay> = HashMap::new();
+    for replay in listed {
+        replays.insert(replay.id.clone(), PlayerReplay { replay: replay.clone(), local_path: None });
+    }
+    for entry in library.existing() {
+        replays
+            .entry(entry.info.id.clone())
+            .or_insert_with(|| PlayerReplay { replay: ReplayItem::from(entry.info.clone()), local_path: None })
+            .local_path = Some(entry.path.clone());
+    }
+
+    let mut by_user: HashMap<String, Vec<PlayerReplay>> = HashMap::new();
+    for replay in replays.into_values() {
+        for user in &replay.replay.users {
+            by_user.entry(user.clone()).or_default().push(replay.clone());
+        }
+    }
+
+    by_user
+        .into_iter()
+        .map(|(user_id, mut replays)| {
+            replays.sort_by_cached_key(|replay| Reverse(parse_created_date(&replay.replay.created_date).ok()));
+            let mut maps = BTreeMap::new();
+            let mut modes = BTreeMap::new();
+            for replay in &replays {
+                *maps.entry(replay.replay.map_name.clone()).or_insert(0) += 1;
+                *modes.entry(replay.replay.game_mode.clone()).or_insert(0) += 1;
+            }
+            PlayerStats {
+                user_id,
+                maps: by_count(maps),
+                modes: by_count(modes),
+                last_seen: replays.iter().find_map(|replay| parse_created_date(&replay.replay.created_date).ok()),
+                replays,
+            }
+        })
+        .collect()
+}
+
+/// Order of the player list.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum PlayerSort {
+    #[default]
+    Matches,
+    LastSeen,
+    Name,
+}
+
+impl PlayerSort {
+    pub const ALL: [PlayerSort; 3] = [PlayerSort::Matches, PlayerSort::LastSeen, PlayerSort::Name];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlayerSort::Matches => "Most matches",
+            PlayerSort::LastSeen => "Last seen",
+            PlayerSort::Name => "Name",
+        }
+    }
+
+    /// Sorts `players`, naming them after their address book entry.
+    pub fn apply(&self, players: &mut [PlayerStats], book: &AddressBook) {
+        match self {
+            PlayerSort::Matches => players.sort_by(|a, b| b.matches().cmp(&a.matches()).then_with(|| b.last_seen.cmp(&a.last_seen))),
+            PlayerSort::LastSeen => players.sort_by_key(|player| Reverse(player.last_seen)),
+            PlayerSort::Name => players.sort_by_cached_key(|player| book.display_name(&player.user_id).to_lowercase()),
+        }
+    }
+}
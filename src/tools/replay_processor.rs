@@ -1,10 +1,12 @@
-use chrono::DateTime;
+use chrono::{DateTime, FixedOffset};
 use rayon::prelude::*;
 use reqwest::blocking::{Client, Response};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     error::Error,
     fs,
+    io::Read,
     path::{Path, PathBuf},
     sync::Arc,
     thread::sleep,
@@ -13,6 +15,9 @@ use std::{
 
 use crate::tools::build_meta::build_meta;
 use crate::tools::build_replay::{build_replay, ReplayPart};
+use crate::tools::error::{ErrorKind, ReplayError};
+use crate::tools::http_client;
+use crate::tools::listing::{ListQuery, PAGE_SIZE};
 
 pub const API_BASE_URL: &str = "https://tv.vankrupt.net";
 
@@ -22,13 +27,33 @@ pub struct DownloadProgress {
     pub build: ProgressUpdate,
 }
 
+/// A downloaded and built replay together with the data it was built from.
+pub struct DownloadedReplay {
+    pub data: Vec<u8>,
+    pub meta: MetaData,
+    pub info: ApiReplay,
+}
+
+/// The parts of a downloaded replay before they are assembled into a file.
+pub struct DownloadedParts {
+    pub parts: Vec<ReplayPart>,
+    pub meta: MetaData,
+    pub info: ApiReplay,
+}
+
+/// A replay built from a local `replay_chunks` directory.
+pub struct ProcessedReplay {
+    pub data: Vec<u8>,
+    pub meta: MetaData,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct ApiResponse {
     pub replays: Vec<ApiReplay>,
     pub total: i32,
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct ApiReplay {
     #[serde(rename = "_id")]
     pub id: String,
@@ -48,25 +73,106 @@ pub struct ApiReplay {
     pub modcount: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ReplayItem {
     pub id: String,
     pub game_mode: String,
     pub map_name: String,
     pub created_date: String,
+    pub expires: String,
     pub time_since: i32,
-    #[allow(dead_code)]
     pub competitive: bool,
-    #[allow(dead_code)]
     pub modcount: i32,
-    #[allow(dead_code)]
     pub shack: bool,
     pub workshop_mods: String,
-    #[allow(dead_code)]
     pub live: bool,
     pub users: Vec<String>,
 }
 
+impl From<ApiReplay> for ReplayItem {
+    fn from(r: ApiReplay) -> Self {
+        Self {
+            id: r.id,
+            game_mode: r.game_mode,
+            map_name: r.map_name,
+            created_date: r.created,
+            expires: r.expires,
+            time_since: r.time_since,
+            competitive: r.competitive,
+            modcount: r.modcount,
+            shack: r.shack,
+            workshop_mods: r.workshop_mods,
+            live: r.live,
+            users: r.users.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&ReplayItem> for ApiReplay {
+    fn from(r: &ReplayItem) -> Self {
+        Self {
+            id: r.id.clone(),
+            game_mode: r.game_mode.clone(),
+            map_name: r.map_name.clone(),
+            shack: r.shack,
+            created: r.created_date.clone(),
+            expires: r.expires.clone(),
+            time_since: r.time_since,
+            workshop_mods: r.workshop_mods.clone(),
+            competitive: r.competitive,
+            live: r.live,
+            users: Some(r.users.clone()),
+            modcount: r.modcount,
+        }
+    }
+}
+
+/// Listing entries for unit tests. Fields a test cares about are set with struct update syntax.
+#[cfg(test)]
+pub mod test_replays {
+    use super::{ApiReplay, ReplayItem};
+
+    /// A finished, casual SND match on Datacenter without mods.
+    pub fn api_replay(id: &str, users: &[&str]) -> ApiReplay {
+        ApiReplay {
+            id: id.to_string(),
+            game_mode: "SND".to_string(),
+            map_name: "Datacenter".to_string(),
+            shack: false,
+            created: "2024-01-31T20:15:00.000Z".to_string(),
+            expires: String::new(),
+            time_since: 0,
+            workshop_mods: String::new(),
+            competitive: false,
+            live: false,
+            users: Some(users.iter().map(|user| user.to_string()).collect()),
+            modcount: 0,
+        }
+    }
+
+    pub fn replay_item(id: &str, users: &[&str]) -> ReplayItem {
+        ReplayItem::from(api_replay(id, users))
+    }
+}
+
+/// Workshop mod IDs in a `workshop_mods` string such as `AdditionalMods=UGC123/...-UGC456/...`.
+pub fn parse_mod_ids(workshop_mods: &str) -> Vec<String> {
+    let mut mod_ids = Vec::new();
+
+    let cleaned_str = workshop_mods.strip_prefix("AdditionalMods=").unwrap_or(workshop_mods);
+
+    for part in cleaned_str.split('-') {
+        if part.starts_with("UGC") {
+            if let Some(slash_pos) = part.find('/') {
+                let id = &part[3..slash_pos];
+                mod_ids.push(id.to_string());
+            }
+        }
+    }
+
+    mod_ids
+}
+
 pub struct Config {
     pub update_callback: Box<dyn Fn(Progress) + Send + Sync>,
     pub data_count: usize,
@@ -78,7 +184,7 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             update_callback: Box::new(|progress| {
-                println!("Progress: {:?}", progress);
+                log::debug!("Progress: {:?}", progress);
             }),
             data_count: usize::MAX,
             event_count: usize::MAX,
@@ -87,7 +193,7 @@ impl Default for Config {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Progress {
     pub header: ProgressUpdate,
     pub data_chunks: ProgressUpdate,
@@ -95,7 +201,7 @@ pub struct Progress {
     pub checkpoint_chunks: ProgressUpdate,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct ProgressUpdate {
     pub current: usize,
     pub max: usize,
@@ -175,6 +281,26 @@ pub struct Chunk {
     pub size_in_bytes: Option<i32>,
 }
 
+/// A 404 means the replay is gone; any other error status is treated as a server problem.
+pub fn status_error_kind(status: reqwest::StatusCode) -> ErrorKind {
+    if status == reqwest::StatusCode::NOT_FOUND {
+        ErrorKind::NotFound
+    } else {
+        ErrorKind::Network
+    }
+}
+
+/// Reads a file body. Unlike `Response::bytes`, which has to finish within the request
+/// timeout, every read gets the full timeout, so large chunks on a slow connection only fail
+/// once the transfer stalls.
+fn read_body(mut response: Response) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    let mut data = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+    response.read_to_end(&mut data).map_err(|e| {
+        ReplayError::new(ErrorKind::Network, format!("Failed to read {}: {}", response.url(), e))
+    })?;
+    Ok(data)
+}
+
 fn get_with_retry(
     client: &Client,
     url: &str,
@@ -186,12 +312,18 @@ fn get_with_retry(
         match client.get(url).send() {
             Ok(resp) if resp.status().is_success() => return Ok(resp),
             Ok(resp) => {
-                return Err(format!("GET {} failed with status: {}", url, resp.status()).into());
+                return Err(ReplayError::new(
+                    status_error_kind(resp.status()),
+                    format!("GET {} failed with status: {}", url, resp.status()),
+                ).into());
             }
             Err(e) => {
                 attempt += 1;
                 if attempt >= max_retries {
-                    return Err(format!("GET {} failed after {} attempts: {}", url, attempt, e).into());
+                    return Err(ReplayError::new(
+                        ErrorKind::Network,
+                        format!("GET {} failed after {} attempts: {}", url, attempt, e),
+                    ).into());
                 }
                 sleep(backoff);
                 backoff *= 2;
@@ -211,12 +343,18 @@ fn post_with_retry(
         match client.post(url).send() {
             Ok(resp) if resp.status().is_success() => return Ok(resp),
             Ok(resp) => {
-                return Err(format!("POST {} failed with status: {}", url, resp.status()).into());
+                return Err(ReplayError::new(
+                    status_error_kind(resp.status()),
+                    format!("POST {} failed with status: {}", url, resp.status()),
+                ).into());
             }
             Err(e) => {
                 attempt += 1;
                 if attempt >= max_retries {
-                    return Err(format!("POST {} failed after {} attempts: {}", url, attempt, e).into());
+                    return Err(ReplayError::new(
+                        ErrorKind::Network,
+                        format!("POST {} failed after {} attempts: {}", url, attempt, e),
+                    ).into());
                 }
                 sleep(backoff);
                 backoff *= 2;
@@ -225,44 +363,146 @@ fn post_with_retry(
     }
 }
 
-pub fn download_replay(
-    replay_id: &str,
-    progress_callback: Option<Box<dyn Fn(usize, usize) + Send + Sync>>
-) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
-    // Validate replay id (only accept alphanumeric IDs)
-    if !replay_id.chars().all(|c| c.is_alphanumeric()) {
-        return Err("Invalid replay id".into());
+/// Parses the `created` field of replay metadata, which is either RFC 3339 or a unix timestamp.
+pub fn parse_created_date(created: &str) -> Result<DateTime<FixedOffset>, Box<dyn Error + Send + Sync>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(created) {
+        return Ok(dt);
     }
+    let ts = created
+        .parse::<i64>()
+        .map_err(|e| format!("Invalid timestamp format: {}", e))?;
+    DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.fixed_offset())
+        .ok_or_else(|| "Invalid timestamp".into())
+}
 
-    const SERVER: &str = API_BASE_URL;
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()?;
+pub fn fetch_metadata(replay_id: &str) -> Result<MetaData, Box<dyn Error + Send + Sync>> {
+    let resp = match http_client::replay_api_client()
+        .get(format!("{}/meta/{}", API_BASE_URL, replay_id))
+        .send() {
+            Ok(resp) => resp,
+            Err(e) => {
+                let message = if e.is_timeout() {
+                    "Connection timed out while fetching replay metadata.".to_string()
+                } else if e.is_connect() {
+                    "Failed to connect to metadata server. Please check your internet connection.".to_string()
+                } else {
+                    format!("Network error retrieving metadata: {}", e)
+                };
+                return Err(ReplayError::new(ErrorKind::Network, message).into());
+            }
+        };
+
+    if !resp.status().is_success() {
+        return Err(ReplayError::new(status_error_kind(resp.status()), format!(
+            "Failed to fetch replay metadata: Server returned {} - {}",
+            resp.status().as_u16(),
+            resp.status().canonical_reason().unwrap_or("Unknown error")
+        )).into());
+    }
+
+    resp.json::<MetaData>().map_err(|e| ReplayError::new(ErrorKind::Parse, format!(
+        "Failed to parse replay metadata: {}. The API format may have changed.", e
+    )).into())
+}
+
+pub fn is_valid_replay_id(replay_id: &str) -> bool {
+    !replay_id.is_empty() && replay_id.chars().all(|c| c.is_alphanumeric())
+}
+
+/// Walks the listing pages until each of `replay_ids` is found or the listing ends. Matches
+/// still being recorded are looked up in the live listing, so they can be told apart from
+/// IDs that are not listed at all (expired or never recorded), which are missing from the
+/// result.
+pub fn find_replays(replay_ids: &[String]) -> Result<HashMap<String, ApiReplay>, Box<dyn Error + Send + Sync>> {
+    let mut found = HashMap::new();
+    for live in [false, true] {
+        if found.len() == replay_ids.len() {
+            break;
+        }
+        find_listed(replay_ids, live, &mut found)?;
+    }
+    Ok(found)
+}
 
+fn find_listed(
+    replay_ids: &[String],
+    live: bool,
+    found: &mut HashMap<String, ApiReplay>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let client = http_client::replay_api_client();
     let max_retries = 5; // maximum retry attempts
-    
-    let mut replay_data = serde_json::Map::new();
     let mut offset = 0;
-    let mut find_all_response = None;
 
-    // Loop through available pages to find the matching replay.
-    while find_all_response.is_none() {
-        let url = format!("{}/find/?game=all&offset={}&live=false", SERVER, offset);
+    // Loop through available pages to find the matching replays.
+    while found.len() < replay_ids.len() {
+        let url = ListQuery { offset, live, ..Default::default() }.url();
         let find_all: ApiResponse = get_with_retry(&client, &url, max_retries)?.json()?;
 
-        find_all_response = find_all
-            .replays
-            .iter()
-            .find(|r| r.id == replay_id)
-            .cloned();
+        for replay in find_all.replays {
+            if replay_ids.contains(&replay.id) {
+                found.insert(replay.id.clone(), replay);
+            }
+        }
 
         if offset >= find_all.total as usize {
             break;
         }
-        offset += 100;
+        offset += PAGE_SIZE;
     }
+    Ok(())
+}
+
+pub fn download_replay<'a>(
+    replay_id: &str,
+    progress_callback: Option<Box<dyn Fn(usize, usize) + Send + Sync + 'a>>
+) -> Result<DownloadedReplay, Box<dyn Error + Send + Sync>> {
+    download_replay_with_info(find_replay(replay_id)?, progress_callback)
+}
+
+/// Looks up the listing entry of a single replay.
+pub fn find_replay(replay_id: &str) -> Result<ApiReplay, Box<dyn Error + Send + Sync>> {
+    // Validate replay id (only accept alphanumeric IDs)
+    if !is_valid_replay_id(replay_id) {
+        return Err(ReplayError::new(ErrorKind::NotFound, "Invalid replay id").into());
+    }
+
+    Ok(find_replays(&[replay_id.to_string()])?
+        .remove(replay_id)
+        .ok_or_else(|| ReplayError::new(ErrorKind::NotFound, "Recording not available"))?)
+}
+
+/// Recording state reported by the server, `Recorded` once the match has finished.
+pub fn replay_state(replay_id: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let url = format!("{}/replay/{}/startDownloading?user", API_BASE_URL, replay_id);
+    let response: serde_json::Value = post_with_retry(&http_client::replay_api_client(), &url, 5)?.json()?;
+    Ok(response["state"].as_str().unwrap_or_default().to_string())
+}
+
+/// Downloads a replay whose listing entry is already known, skipping the `/find/` lookup.
+pub fn download_replay_with_info<'a>(
+    replay_info: ApiReplay,
+    progress_callback: Option<Box<dyn Fn(usize, usize) + Send + Sync + 'a>>
+) -> Result<DownloadedReplay, Box<dyn Error + Send + Sync>> {
+    let downloaded = download_replay_parts(replay_info, progress_callback)?;
+    let data = build_replay(&downloaded.parts)
+        .map_err(|e| ReplayError::new(ErrorKind::Parse, e.to_string()))?;
+    Ok(DownloadedReplay { data, meta: downloaded.meta, info: downloaded.info })
+}
+
+/// Downloads every part of a replay without assembling the file, so it can be streamed
+/// with `write_replay`.
+pub fn download_replay_parts<'a>(
+    replay_info: ApiReplay,
+    progress_callback: Option<Box<dyn Fn(usize, usize) + Send + Sync + 'a>>
+) -> Result<DownloadedParts, Box<dyn Error + Send + Sync>> {
+    const SERVER: &str = API_BASE_URL;
+    let client = http_client::replay_api_client();
+    let replay_id = replay_info.id.as_str();
+
+    let max_retries = 5; // maximum retry attempts
     
-    let replay_info = find_all_response.ok_or("Recording not available")?;
+    let mut replay_data = serde_json::Map::new();
     replay_data.insert("find".into(), serde_json::to_value(&replay_info)?);
     
     let start_url = format!("{}/replay/{}/startDownloading?user", SERVER, replay_id);
@@ -271,7 +511,7 @@ pub fn download_replay(
     replay_data.insert("start_downloading".into(), start_download.clone());
 
     if start_download["state"] != "Recorded" {
-        return Err("Recording must be finished before download".into());
+        return Err(ReplayError::new(ErrorKind::NotRecorded, "Recording must be finished before download").into());
     }
     
     let num_chunks = start_download["numChunks"].as_i64().unwrap_or(0) as usize;
@@ -292,7 +532,7 @@ pub fn download_replay(
     
     // Download header
     let header_url = format!("{}/replay/{}/file/replay.header", SERVER, replay_id);
-    let header_data = get_with_retry(&client, &header_url, max_retries)?.bytes()?.to_vec();
+    let header_data = read_body(get_with_retry(&client, &header_url, max_retries)?)?;
     
     completed_components += 1;
     update_progress(completed_components);
@@ -350,7 +590,7 @@ pub fn download_replay(
                 .get("mtime2")
                 .and_then(|v| v.to_str().ok())
                 .and_then(|s| s.parse().ok());
-            let chunk_data = response.bytes()?.to_vec();
+            let chunk_data = read_body(response)?;
 
             // Update progress after each chunk is downloaded
             let downloaded = downloaded_chunks.fetch_add(1, Ordering::SeqCst) + 1;
@@ -407,15 +647,14 @@ pub fn download_replay(
 
     // Build the replay by first constructing the meta buffer and then appending each chunk.
     let meta_buffer = build_meta(&meta)
-        .map_err(|e| -> Box<dyn Error + Send + Sync> { e.to_string().into() })?;
+        .map_err(|e| ReplayError::new(ErrorKind::Parse, e.to_string()))?;
     let mut parts = vec![ReplayPart::Meta(meta_buffer)];
     parts.extend(download_chunks.into_iter().map(ReplayPart::Chunk));
     
     // Final progress update
     update_progress(total_components);
 
-    build_replay(&parts)
-        .map_err(|e| -> Box<dyn Error + Send + Sync> { e.to_string().into() })
+    Ok(DownloadedParts { parts, meta, info: replay_info })
 }
 
 pub fn replay_chunks_dir() -> PathBuf {
@@ -442,9 +681,18 @@ pub fn load_chunk_file(file_path: &Path) -> Result<Vec<u8>, Box<dyn Error>> {
     Ok(fs::read(file_path)?)
 }
 
-pub fn process_replay(config: Option<Config>) -> Result<Vec<u8>, Box<dyn Error>> {
+/// Builds a replay from the chunk files of a legacy `replay_chunks` directory
+/// (`metadata.json`, `timing.json`, `replay.header` and `stream.N`).
+pub fn process_replay(chunks_dir: &Path, config: Option<Config>) -> Result<ProcessedReplay, Box<dyn Error>> {
+    let (parts, meta) = load_replay_parts(chunks_dir, config)?;
+    let data = build_replay(&parts)?;
+    Ok(ProcessedReplay { data, meta })
+}
+
+/// Reads the parts of a replay from a legacy `replay_chunks` directory without assembling
+/// the file, so it can be streamed with `write_replay`.
+pub fn load_replay_parts(chunks_dir: &Path, config: Option<Config>) -> Result<(Vec<ReplayPart>, MetaData), Box<dyn Error>> {
     let config = config.unwrap_or_default();
-    let chunks_dir = replay_chunks_dir();
     let metadata_path = chunks_dir.join("metadata.json");
     let timing_path = chunks_dir.join("timing.json");
 
@@ -453,7 +701,7 @@ pub fn process_replay(config: Option<Config>) -> Result<Vec<u8>, Box<dyn Error>>
 
     let meta = metadata_file
         .meta
-        .ok_or_else(|| "Invalid metadata: missing 'meta' field")?;
+        .ok_or("Invalid metadata: missing 'meta' field")?;
 
     let update_callback = &config.update_callback;
     let mut download_chunks: Vec<Chunk> = Vec::new();
@@ -484,7 +732,7 @@ pub fn process_replay(config: Option<Config>) -> Result<Vec<u8>, Box<dyn Error>>
         size_in_bytes: None,
     });
 
-    let mut stream_files: Vec<PathBuf> = fs::read_dir(&chunks_dir)?
+    let mut stream_files: Vec<PathBuf> = fs::read_dir(chunks_dir)?
         .filter_map(|entry| entry.ok().map(|e| e.path()))
         .filter(|p| p.file_name().map(|f| f.to_string_lossy().starts_with("stream.")).unwrap_or(false))
         .collect();
@@ -604,21 +852,5 @@ pub fn process_replay(config: Option<Config>) -> Result<Vec<u8>, Box<dyn Error>>
     let mut parts = vec![ReplayPart::Meta(meta_buffer)];
     parts.extend(download_chunks.into_iter().map(ReplayPart::Chunk));
 
-    let replay = build_replay(&parts)?;
-    let created_datetime = DateTime::parse_from_rfc3339(&meta.created)
-        .or_else(|_| -> Result<_, Box<dyn Error>> {
-            let ts = meta.created
-                .parse::<i64>()
-                .map_err(|e| Box::new(e) as Box<dyn Error>)?;
-            DateTime::from_timestamp(ts, 0)
-                .map(|dt| dt.fixed_offset())
-                .ok_or_else(|| "Invalid timestamp".into())
-        })?;
-
-    let formatted_date = created_datetime.format("%Y.%m.%d-%H.%M.%S");
-    let sanitized_name = meta.friendly_name.replace([' ', '/', '\\', ':'], "-");
-    let filename = format!("{}-{}-{}.replay", sanitized_name, meta.game_mode, formatted_date);
-    let output_path = std::env::current_dir()?.join(filename);
-    fs::write(&output_path, &replay)?;
-    Ok(replay)
+    Ok((parts, meta))
 }
\ No newline at end of file
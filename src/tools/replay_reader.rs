@@ -0,0 +1,141 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+
+/// Magic number written at the start of the meta block by `build_meta`.
+const META_MAGIC: i32 = 0x1CA2E27F;
+const FRIENDLY_NAME_SIZE: usize = 514;
+const META_SIZE: usize = 48 + FRIENDLY_NAME_SIZE;
+/// Ticks between 0001-01-01 and the unix epoch, as used by the meta timestamp.
+const UNIX_EPOCH_TICKS: i64 = 621355968000000000;
+
+/// Summary of a built `.replay` file, read back from its meta block and chunk table.
+#[derive(Debug, Clone, Default)]
+pub struct ReplaySummary {
+    pub file_size: usize,
+    pub total_time: i32,
+    pub version: i32,
+    pub game_mode: String,
+    pub map_name: String,
+    pub competitive: bool,
+    pub workshop_mods: String,
+    pub live: bool,
+    pub created: Option<DateTime<Utc>>,
+    pub header_chunks: usize,
+    pub data_chunks: usize,
+    pub checkpoint_chunks: usize,
+    pub event_chunks: usize,
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Result<i32, Box<dyn Error>> {
+    data.get(offset..offset + 4)
+        .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| format!("Unexpected end of file at offset {}", offset).into())
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Result<i64, Box<dyn Error>> {
+    data.get(offset..offset + 8)
+        .map(|b| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(b);
+            i64::from_le_bytes(buf)
+        })
+        .ok_or_else(|| format!("Unexpected end of file at offset {}", offset).into())
+}
+
+/// Reads the length-prefixed, null terminated strings used by checkpoint/event chunks.
+fn skip_string_buffer(body: &[u8], offset: usize) -> Result<usize, Box<dyn Error>> {
+    let len = read_i32(body, offset)?;
+    if len < 0 || offset + 4 + len as usize > body.len() {
+        return Err(format!("Invalid string length {} in event chunk", len).into());
+    }
+    Ok(offset + 4 + len as usize)
+}
+
+fn validate_chunk_body(chunk_type: u32, body: &[u8]) -> Result<(), Box<dyn Error>> {
+    match chunk_type {
+        1 => {
+            let data_len = read_i32(body, 8)?;
+            if data_len < 0 || 16 + data_len as usize != body.len() {
+                return Err(format!("Data chunk length {} does not match body size {}", data_len, body.len()).into());
+            }
+        }
+        2 | 3 => {
+            let mut offset = 0;
+            for _ in 0..3 {
+                offset = skip_string_buffer(body, offset)?;
+            }
+            let data_len = read_i32(body, offset + 8)?;
+            if data_len < 0 || offset + 12 + data_len as usize != body.len() {
+                return Err(format!("Event chunk length {} does not match body size {}", data_len, body.len()).into());
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Parses and validates a replay produced by `build_replay`.
+pub fn read_replay_summary(data: &[u8]) -> Result<ReplaySummary, Box<dyn Error>> {
+    if data.len() < META_SIZE {
+        return Err(format!("File is too small to be a replay ({} bytes)", data.len()).into());
+    }
+
+    let magic = read_i32(data, 0)?;
+    if magic != META_MAGIC {
+        return Err(format!("Invalid magic number {:#010X}", magic).into());
+    }
+
+    let mut summary = ReplaySummary {
+        file_size: data.len(),
+        total_time: read_i32(data, 8)?,
+        version: read_i32(data, 12)?,
+        ..Default::default()
+    };
+
+    let name_bytes = &data[24..24 + FRIENDLY_NAME_SIZE];
+    let name_units: Vec<u16> = name_bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    let friendly_name = String::from_utf16_lossy(&name_units);
+    let fields: Vec<&str> = friendly_name.trim_end().split(',').collect();
+    summary.game_mode = fields.first().unwrap_or(&"").to_string();
+    summary.map_name = fields.get(1).unwrap_or(&"").to_string();
+    summary.competitive = fields.get(2) == Some(&"competitive");
+    summary.workshop_mods = fields.get(4).unwrap_or(&"").to_string();
+
+    let live_offset = 24 + FRIENDLY_NAME_SIZE;
+    summary.live = read_i32(data, live_offset)? != 0;
+    let ticks = read_i64(data, live_offset + 4)?;
+    summary.created = DateTime::from_timestamp_millis((ticks - UNIX_EPOCH_TICKS) / 10000);
+
+    let mut offset = META_SIZE;
+    while offset < data.len() {
+        let chunk_type = read_i32(data, offset)? as u32;
+        let body_len = read_i32(data, offset + 4)?;
+        let body_start = offset + 8;
+        if body_len < 0 || body_start + body_len as usize > data.len() {
+            return Err(format!("Chunk at offset {} overruns the file (length {})", offset, body_len).into());
+        }
+        let body = &data[body_start..body_start + body_len as usize];
+        validate_chunk_body(chunk_type, body)
+            .map_err(|e| format!("Chunk at offset {}: {}", offset, e))?;
+
+        match chunk_type {
+            0 => summary.header_chunks += 1,
+            1 => summary.data_chunks += 1,
+            2 => summary.checkpoint_chunks += 1,
+            3 => summary.event_chunks += 1,
+            other => return Err(format!("Unknown chunk type {} at offset {}", other, offset).into()),
+        }
+        offset = body_start + body_len as usize;
+    }
+
+    if summary.header_chunks != 1 {
+        return Err(format!("Expected exactly one header chunk, found {}", summary.header_chunks).into());
+    }
+
+    Ok(summary)
+}
@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use rhai::{Dynamic, Engine, Map, AST};
+
+use crate::tools::naming::sanitize_relative_path;
+use crate::tools::replay_processor::ApiReplay;
+
+/// Name of the function a download script must define.
+pub const ENTRY_POINT: &str = "decide";
+
+/// Upper bound on the operations one call may run, so a runaway loop in a script cannot
+/// freeze the window or the watch daemon.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// What a download script decided for one replay.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptDecision {
+    Skip,
+    /// Download the replay, under `file_name` (relative to the download directory) if the
+    /// script chose one, otherwise with the configured name template.
+    Download { file_name: Option<PathBuf> },
+}
+
+/// A [rhai](https://rhai.rs) script deciding which replays to download.
+///
+/// The script defines `fn decide(replay)`, where `replay` is a map with the fields of the
+/// `/find/` listing (`_id`, `gameMode`, `friendlyName`, `users`, ...). It returns `false` or
+/// `()` to skip the replay, `true` to download it, a string to download it under that file
+/// name, or a map such as `#{ download: true, filename: "scrims/" + replay.friendlyName }`.
+pub struct DownloadScript {
+    engine: Engine,
+    ast: AST,
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+impl DownloadScript {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        let source = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read script {}: {}", path.display(), e))?;
+        let ast = engine
+            .compile(source)
+            .map_err(|e| format!("Failed to compile script {}: {}", path.display(), e))?;
+        if !ast.iter_functions().any(|function| function.name == ENTRY_POINT && function.params.len() == 1) {
+            return Err(format!("Script {} does not define fn {}(replay)", path.display(), ENTRY_POINT).into());
+        }
+
+        Ok(Self { engine, ast })
+    }
+
+    pub fn decide(&self, replay: &ApiReplay) -> Result<ScriptDecision, Box<dyn Error + Send + Sync>> {
+        let replay_map: Dynamic = rhai::serde::to_dynamic(replay)
+            .map_err(|e| format!("Failed to pass replay {} to the script: {}", replay.id, e))?;
+        let result: Dynamic = self
+            .engine
+            .call_fn(&mut rhai::Scope::new(), &self.ast, ENTRY_POINT, (replay_map,))
+            .map_err(|e| format!("Script error for replay {}: {}", replay.id, e))?;
+        Self::parse_result(result).map_err(|e| format!("Script error for replay {}: {}", replay.id, e).into())
+    }
+
+    fn parse_result(result: Dynamic) -> Result<ScriptDecision, String> {
+        if result.is_unit() {
+            return Ok(ScriptDecision::Skip);
+        }
+        if let Some(download) = result.clone().try_cast::<bool>() {
+            return Ok(if download { ScriptDecision::Download { file_name: None } } else { ScriptDecision::Skip });
+        }
+        if let Some(file_name) = result.clone().try_cast::<rhai::ImmutableString>() {
+            return Ok(Self::download_as(&file_name));
+        }
+        let type_name = result.type_name();
+        if let Some(map) = result.try_cast::<Map>() {
+            let download = match map.get("download") {
+                Some(value) => value.as_bool().map_err(|_| "'download' must be true or false")?,
+                None => true,
+            };
+            if !download {
+                return Ok(ScriptDecision::Skip);
+            }
+            return match map.get("filename") {
+                Some(value) if value.is_unit() => Ok(ScriptDecision::Download { file_name: None }),
+                Some(value) => value
+                    .clone()
+                    .into_immutable_string()
+                    .map(|file_name| Self::download_as(&file_name))
+                    .map_err(|_| "'filename' must be a string".to_string()),
+                None => Ok(ScriptDecision::Download { file_name: None }),
+            };
+        }
+        Err(format!("{}() must return a bool, a string or a map, not {}", ENTRY_POINT, type_name))
+    }
+
+    fn download_as(file_name: &str) -> ScriptDecision {
+        if file_name.trim().is_empty() {
+            ScriptDecision::Download { file_name: None }
+        } else {
+            ScriptDecision::Download { file_name: Some(sanitize_relative_path(file_name)) }
+        }
+    }
+}
+
+/// Keeps the script configured in settings loaded and reloads it when the path or the file
+/// changes. A script that fails to reload is reported and the previous version keeps running.
+#[derive(Default)]
+pub struct ScriptHost {
+    script: Option<DownloadScript>,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    error: Option<String>,
+}
+
+impl ScriptHost {
+    /// Brings the script up to date with `path`; an empty path disables scripting. Returns
+    /// `Some` when the script was loaded or failed to load.
+    pub fn refresh(&mut self, path: &Path) -> Option<Result<(), String>> {
+        if path.as_os_str().is_empty() {
+            *self = Self::default();
+            return None;
+        }
+
+        let modified = modified_time(path);
+        if path == self.path && modified == self.modified {
+            return None;
+        }
+        if path != self.path {
+            self.script = None;
+        }
+        self.path = path.to_path_buf();
+        self.modified = modified;
+
+        match DownloadScript::load(path) {
+            Ok(script) => {
+                self.script = Some(script);
+                self.error = None;
+                Some(Ok(()))
+            }
+            Err(e) => {
+                self.error = Some(e.to_string());
+                Some(Err(e.to_string()))
+            }
+        }
+    }
+
+    pub fn script(&self) -> Option<&DownloadScript> {
+        self.script.as_ref()
+    }
+
+    /// Error from the last load attempt, if it failed.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decide(expression: &str) -> Result<ScriptDecision, String> {
+        let result = Engine::new().eval::<Dynamic>(expression).unwrap();
+        DownloadScript::parse_result(result)
+    }
+
+    fn download_as(file_name: &str) -> ScriptDecision {
+        ScriptDecision::Download { file_name: Some(PathBuf::from(file_name)) }
+    }
+
+    #[test]
+    fn unit_and_bools() {
+        assert_eq!(decide("()"), Ok(ScriptDecision::Skip));
+        assert_eq!(decide("false"), Ok(ScriptDecision::Skip));
+        assert_eq!(decide("true"), Ok(ScriptDecision::Download { file_name: None }));
+    }
+
+    #[test]
+    fn strings_are_sanitized_file_names() {
+        assert_eq!(decide(r#""scrim: final""#), Ok(download_as("scrim- final.replay")));
+        assert_eq!(decide(r#""  ""#), Ok(ScriptDecision::Download { file_name: None }));
+        assert_eq!(
+            decide(r#""scrims/final""#),
+            Ok(ScriptDecision::Download { file_name: Some(Path::new("scrims").join("final.replay")) })
+        );
+    }
+
+    #[test]
+    fn maps() {
+        assert_eq!(decide("#{}"), Ok(ScriptDecision::Download { file_name: None }));
+        assert_eq!(decide("#{ download: false, filename: \"x\" }"), Ok(ScriptDecision::Skip));
+        assert_eq!(decide("#{ download: true, filename: () }"), Ok(ScriptDecision::Download { file_name: None }));
+        assert_eq!(decide("#{ filename: \"x\" }"), Ok(download_as("x.replay")));
+    }
+
+    #[test]
+    fn rejects_other_values() {
+        assert!(decide("1").is_err());
+        assert!(decide("[true]").is_err());
+        assert!(decide("#{ download: \"yes\" }").is_err());
+        assert!(decide("#{ filename: 1 }").is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::{Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::tools::error::{ErrorKind, ReplayError};
+use crate::tools::http_client::{self, INTERACTIVE_TIMEOUT};
+use crate::tools::library::settings_dir;
+use crate::tools::replay_processor::ReplayItem;
+
+/// A player whose matches raise an alert, with a nickname that is only stored locally.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackedPlayer {
+    pub user_id: String,
+    pub nickname: String,
+}
+
+impl TrackedPlayer {
+    pub fn display_name(&self) -> &str {
+        if self.nickname.is_empty() { &self.user_id } else { &self.nickname }
+    }
+}
+
+/// Whether `user_id` is on the tracked list. IDs are compared case-insensitively.
+pub fn is_tracked(tracked: &[TrackedPlayer], user_id: &str) -> bool {
+    tracked.iter().any(|player| player.user_id.eq_ignore_ascii_case(user_id))
+}
+
+/// File name of the reported sightings in the settings directory.
+const SEEN_FILE: &str = "tracking_seen.json";
+/// Sightings kept so they are not reported again after a restart.
+const SEEN_LIMIT: usize = 2000;
+/// Sightings older than this are forgotten. The server drops replays well before then.
+const SEEN_MAX_AGE: Duration = Duration::days(30);
+
+/// A reported sighting and when it was reported.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct SeenSighting {
+    replay_id: String,
+    /// Lowercase user ID.
+    user_id: String,
+    /// Unix time of the report.
+    seen: i64,
+}
+
+/// Sightings already reported, oldest first. Stored in their own file in the settings
+/// directory, bounded by `SEEN_LIMIT` and `SEEN_MAX_AGE`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SeenSightings(VecDeque<SeenSighting>);
+
+impl SeenSightings {
+    /// Loads the reported sightings, or none if the file does not exist or cannot be read.
+    pub fn load() -> Self {
+        let mut seen: Self = settings_dir()
+            .and_then(|dir| fs::read_to_string(dir.join(SEEN_FILE)))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        seen.forget_before((Utc::now() - SEEN_MAX_AGE).timestamp());
+        seen
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let contents = serde_json::to_string(self)?;
+        fs::write(settings_dir()?.join(SEEN_FILE), contents)
+    }
+
+    /// Records a sighting. Returns `false` if it was already recorded.
+    pub fn insert(&mut self, replay_id: &str, user_id: &str) -> bool {
+        let user_id = user_id.to_lowercase();
+        if self.0.iter().any(|seen| seen.replay_id == replay_id && seen.user_id == user_id) {
+            return false;
+        }
+        let now = Utc::now();
+        self.forget_before((now - SEEN_MAX_AGE).timestamp());
+        if self.0.len() >= SEEN_LIMIT {
+            self.0.pop_front();
+        }
+        self.0.push_back(SeenSighting { replay_id: replay_id.to_string(), user_id, seen: now.timestamp() });
+        true
+    }
+
+    fn forget_before(&mut self, cutoff: i64) {
+        self.0.retain(|seen| seen.seen >= cutoff);
+    }
+}
+
+/// A tracked player found in a replay.
+#[derive(Clone, Debug)]
+pub struct PlayerSighting {
+    pub player: TrackedPlayer,
+    pub replay_id: String,
+    pub map_name: String,
+    pub game_mode: String,
+    pub live: bool,
+}
+
+impl PlayerSighting {
+    pub fn message(&self) -> String {
+        format!(
+            "{} is in {} match on {} ({})",
+            self.player.display_name(),
+            if self.live { "a live" } else { "a new" },
+            self.map_name,
+            self.game_mode
+        )
+    }
+}
+
+/// Tracked players in `replays` that were not reported before. Reported sightings are added
+/// to `seen` so each is reported once.
+pub fn find_sightings(
+    tracked: &[TrackedPlayer],
+    replays: &[ReplayItem],
+    seen: &mut SeenSightings,
+) -> Vec<PlayerSighting> {
+    let mut sightings = Vec::new();
+    for replay in replays {
+        for player in tracked.iter().filter(|player| !player.user_id.is_empty()) {
+            if !replay.users.iter().any(|user| user.eq_ignore_ascii_case(&player.user_id)) {
+                continue;
+            }
+            if !seen.insert(&replay.id, &player.user_id) {
+                continue;
+            }
+            sightings.push(PlayerSighting {
+                player: player.clone(),
+                replay_id: replay.id.clone(),
+                map_name: replay.map_name.clone(),
+                game_mode: replay.game_mode.clone(),
+                live: replay.live,
+            });
+        }
+    }
+    sightings
+}
+
+/// Appends a timestamped line per sighting to `path`.
+pub fn append_to_log(path: &Path, sightings: &[PlayerSighting]) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for sighting in sightings {
+        writeln!(
+            file,
+            "[{}] {} (user {}, replay {})",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            sighting.message(),
+            sighting.player.user_id,
+            sighting.replay_id
+        )?;
+    }
+    Ok(())
+}
+
+/// Posts one JSON event per sighting to `url`. The `content` field carries the message so
+/// chat webhooks such as Discord's display it as is.
+pub fn send_webhook(url: &str, sightings: &[PlayerSighting]) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let client = http_client::client();
+    for sighting in sightings {
+        let body = json!({
+            "event": "player_seen",
+            "content": sighting.message(),
+            "user_id": sighting.player.user_id,
+            "nickname": sighting.player.nickname,
+            "replay_id": sighting.replay_id,
+            "map": sighting.map_name,
+            "game_mode": sighting.game_mode,
+            "live": sighting.live,
+        });
+        let response = client.post(url).timeout(INTERACTIVE_TIMEOUT).json(&body).send()?;
+        if !response.status().is_success() {
+            return Err(ReplayError::new(ErrorKind::Network, format!(
+                "Webhook returned {} - {}",
+                response.status().as_u16(),
+                response.status().canonical_reason().unwrap_or("Unknown error")
+            )).into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::replay_processor::test_replays::replay_item;
+
+    fn player(user_id: &str, nickname: &str) -> TrackedPlayer {
+        TrackedPlayer { user_id: user_id.to_string(), nickname: nickname.to_string() }
+    }
+
+    fn replay(id: &str, users: &[&str], live: bool) -> ReplayItem {
+        ReplayItem { live, ..replay_item(id, users) }
+    }
+
+    fn found(sightings: &[PlayerSighting]) -> Vec<(String, String)> {
+        sightings
+            .iter()
+            .map(|sighting| (sighting.replay_id.clone(), sighting.player.user_id.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_tracked_players_ignoring_case() {
+        let tracked = [player("Alice", "Ace"), player("bob", ""), player("", "nobody")];
+        let replays = [replay("r1", &["alice", "carol"], true), replay("r2", &["BOB", "ALICE"], false)];
+        let sightings = find_sightings(&tracked, &replays, &mut SeenSightings::default());
+        assert_eq!(found(&sightings), [
+            ("r1".to_string(), "Alice".to_string()),
+            ("r2".to_string(), "Alice".to_string()),
+            ("r2".to_string(), "bob".to_string()),
+        ]);
+        assert_eq!(sightings[0].message(), "Ace is in a live match on Datacenter (SND)");
+        assert_eq!(sightings[2].message(), "bob is in a new match on Datacenter (SND)");
+    }
+
+    #[test]
+    fn reports_each_sighting_once() {
+        let tracked = [player("alice", "")];
+        let mut seen = SeenSightings::default();
+        assert_eq!(find_sightings(&tracked, &[replay("r1", &["alice"], true)], &mut seen).len(), 1);
+        // The same match again, for example after it finished or after a restart.
+        assert!(find_sightings(&[player("ALICE", "")], &[replay("r1", &["alice"], false)], &mut seen).is_empty());
+        assert_eq!(find_sightings(&tracked, &[replay("r2", &["alice"], false)], &mut seen).len(), 1);
+    }
+
+    #[test]
+    fn seen_sightings_survive_a_round_trip_and_stay_bounded() {
+        let mut seen = SeenSightings::default();
+        for index in 0..=SEEN_LIMIT {
+            assert!(seen.insert(&index.to_string(), "alice"));
+        }
+        assert_eq!(seen.0.len(), SEEN_LIMIT);
+        assert!(seen.insert("0", "alice"), "the oldest sighting is forgotten first");
+
+        let mut restored: SeenSightings = serde_json::from_str(&serde_json::to_string(&seen).unwrap()).unwrap();
+        assert!(!restored.insert(&SEEN_LIMIT.to_string(), "ALICE"));
+    }
+
+    #[test]
+    fn old_sightings_are_forgotten() {
+        let mut seen = SeenSightings::default();
+        assert!(seen.insert("r1", "alice"));
+        assert!(seen.insert("r2", "alice"));
+        seen.0[0].seen -= (SEEN_MAX_AGE + Duration::days(1)).num_seconds();
+        assert!(seen.insert("r3", "alice"));
+        assert_eq!(seen.0.len(), 2);
+        assert!(seen.insert("r1", "alice"));
+        assert!(!seen.insert("r2", "alice"));
+    }
+}
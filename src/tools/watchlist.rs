@@ -0,0 +1,122 @@
+use chrono::{TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::tools::replay_processor::{parse_created_date, ReplayItem};
+
+/// Watched replays are downloaded once they are this close to expiring, unless configured otherwise.
+pub const DEFAULT_EXPIRY_WINDOW_HOURS: u64 = 24;
+
+/// Replay the user wants to keep an eye on, stored in the settings file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchedReplay {
+    pub id: String,
+    pub map_name: String,
+    pub game_mode: String,
+    pub created: String,
+    /// Empty for entries added before expiry dates were stored, until the replay shows up
+    /// in a listing again.
+    pub expires: String,
+}
+
+impl WatchedReplay {
+    /// Whether the replay expires within `window_hours` and can still be downloaded.
+    pub fn is_due(&self, window_hours: u64) -> bool {
+        let window = TimeDelta::hours(window_hours.min(24 * 365) as i64);
+        time_until_expiry(&self.expires).is_some_and(|left| left > TimeDelta::zero() && left <= window)
+    }
+}
+
+impl From<&ReplayItem> for WatchedReplay {
+    fn from(replay: &ReplayItem) -> Self {
+        Self {
+            id: replay.id.clone(),
+            map_name: replay.map_name.clone(),
+            game_mode: replay.game_mode.clone(),
+            created: replay.created_date.clone(),
+            expires: replay.expires.clone(),
+        }
+    }
+}
+
+/// Adds the replays that are not on the watchlist yet and returns how many were added.
+pub fn add_to_watchlist<'a>(watchlist: &mut Vec<WatchedReplay>, replays: impl IntoIterator<Item = &'a ReplayItem>) -> usize {
+    let before = watchlist.len();
+    for replay in replays {
+        if !watchlist.iter().any(|entry| entry.id == replay.id) {
+            watchlist.push(WatchedReplay::from(replay));
+        }
+    }
+    watchlist.len() - before
+}
+
+/// Copies expiry dates from freshly listed replays into the watchlist. Returns whether
+/// anything changed.
+pub fn update_expiry(watchlist: &mut [WatchedReplay], replays: &[ReplayItem]) -> bool {
+    let mut changed = false;
+    for entry in watchlist.iter_mut() {
+        if let Some(replay) = replays.iter().find(|replay| replay.id == entry.id) {
+            if entry.expires != replay.expires {
+                entry.expires = replay.expires.clone();
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// Time left until `expires`, negative once it has passed. `None` if the date cannot be parsed.
+pub fn time_until_expiry(expires: &str) -> Option<TimeDelta> {
+    parse_created_date(expires).ok().map(|expires| expires.to_utc() - Utc::now())
+}
+
+/// Short countdown such as `expires in 2d 4h`, `expires in 8m` or `expired`.
+pub fn expiry_text(remaining: TimeDelta) -> String {
+    if remaining <= TimeDelta::zero() {
+        return "expired".to_string();
+    }
+    let (days, hours, minutes) = (remaining.num_days(), remaining.num_hours() % 24, remaining.num_minutes() % 60);
+    if days > 0 {
+        format!("expires in {}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("expires in {}h {}m", hours, minutes)
+    } else {
+        format!("expires in {}m", minutes.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expiring_in(delta: TimeDelta) -> WatchedReplay {
+        WatchedReplay { id: "abc123".to_string(), expires: (Utc::now() + delta).to_rfc3339(), ..Default::default() }
+    }
+
+    #[test]
+    fn due_within_the_window() {
+        assert!(expiring_in(TimeDelta::hours(2)).is_due(DEFAULT_EXPIRY_WINDOW_HOURS));
+        assert!(expiring_in(TimeDelta::minutes(1)).is_due(1));
+        assert!(!expiring_in(TimeDelta::hours(30)).is_due(DEFAULT_EXPIRY_WINDOW_HOURS));
+        assert!(!expiring_in(TimeDelta::hours(2)).is_due(0));
+    }
+
+    #[test]
+    fn expired_or_unknown_replays_are_not_due() {
+        assert!(!expiring_in(TimeDelta::hours(-1)).is_due(DEFAULT_EXPIRY_WINDOW_HOURS));
+        assert!(!WatchedReplay::default().is_due(DEFAULT_EXPIRY_WINDOW_HOURS));
+    }
+
+    #[test]
+    fn huge_windows_do_not_overflow() {
+        assert!(expiring_in(TimeDelta::days(300)).is_due(u64::MAX));
+    }
+
+    #[test]
+    fn describes_time_left() {
+        assert_eq!(expiry_text(TimeDelta::zero()), "expired");
+        assert_eq!(expiry_text(TimeDelta::hours(52)), "expires in 2d 4h");
+        assert_eq!(expiry_text(TimeDelta::minutes(125)), "expires in 2h 5m");
+        assert_eq!(expiry_text(TimeDelta::seconds(20)), "expires in 1m");
+    }
+}